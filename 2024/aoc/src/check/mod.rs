@@ -0,0 +1,100 @@
+use crate::util::prop::Check;
+use crate::{day08, day13, day15, day17, day18};
+
+const DEFAULT_SEED: u64 = 0;
+const DEFAULT_CASES: usize = 1000;
+
+fn properties() -> Vec<Box<dyn Check>> {
+    let mut properties = vec![];
+    properties.extend(day08::properties());
+    properties.extend(day13::properties());
+    properties.extend(day15::properties());
+    properties.extend(day17::properties());
+    properties.extend(day18::properties());
+    properties
+}
+
+// aoc check [name ...] [--seed S] [--cases N]
+pub fn main(args: Vec<String>) {
+    let mut seed = DEFAULT_SEED;
+    let mut cases = DEFAULT_CASES;
+    let mut names = vec![];
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--seed" => {
+                seed = args[i + 1].parse().unwrap();
+                i += 1;
+            }
+            "--cases" => {
+                cases = args[i + 1].parse().unwrap();
+                i += 1;
+            }
+            name => names.push(name.to_string()),
+        }
+        i += 1;
+    }
+
+    let mut failed = false;
+    for property in properties() {
+        if !names.is_empty() && !names.iter().any(|n| n == property.name()) {
+            continue;
+        }
+        match property.run(seed, cases) {
+            Ok(outcome) => println!(
+                "{}: ok ({} passed, {} skipped)",
+                property.name(),
+                outcome.passed,
+                outcome.skipped
+            ),
+            Err(failure) => {
+                println!("{}", failure);
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The same run as `aoc check <name>`, failing the test with the shrunk case
+    fn check(name: &str) {
+        let property = properties()
+            .into_iter()
+            .find(|p| p.name() == name)
+            .unwrap_or_else(|| panic!("no property {}", name));
+        if let Err(failure) = property.run(DEFAULT_SEED, DEFAULT_CASES) {
+            panic!("{}", failure);
+        }
+    }
+
+    #[test]
+    fn day08_antinodes() {
+        check("day08-antinodes");
+    }
+
+    #[test]
+    fn day13_cost() {
+        check("day13-cost");
+    }
+
+    #[test]
+    fn day15_wide_moves() {
+        check("day15-wide-moves");
+    }
+
+    #[test]
+    fn day15_wide_invariants() {
+        check("day15-wide-invariants");
+    }
+
+    #[test]
+    fn day18_generated() {
+        check("day18-generated");
+    }
+}
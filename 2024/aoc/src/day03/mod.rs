@@ -1,16 +1,8 @@
+use crate::util::input;
 use regex::Regex;
-use std::fs;
-
-const DEFAULT_INPUT_FILE: &str = "input.txt";
 
 pub fn main(_args: Vec<String>) {
-    let this_file = file!();
-    let this_dir = std::path::Path::new(this_file).parent().unwrap();
-    let mut file_name = DEFAULT_INPUT_FILE;
-    if _args.len() >= 1 {
-        file_name = &_args[0];
-    }
-    let input = fs::read_to_string(this_dir.join(file_name)).unwrap();
+    let input = input::load(file!(), &_args);
 
     let parsed = parse_input(&input);
     let result = part1(&parsed);
@@ -1,19 +1,14 @@
+use crate::util::{
+    input,
+    parse::{self, ParseError},
+};
 use itertools::iproduct;
-use regex::Regex;
-use std::{collections::HashSet, fs};
-
-const DEFAULT_INPUT_FILE: &str = "input.txt";
+use std::collections::HashSet;
 
 pub fn main(_args: Vec<String>) {
-    let this_file = file!();
-    let this_dir = std::path::Path::new(this_file).parent().unwrap();
-    let mut file_name = DEFAULT_INPUT_FILE;
-    if _args.len() >= 1 {
-        file_name = &_args[0];
-    }
-    let input = fs::read_to_string(this_dir.join(file_name)).unwrap();
+    let input = input::load(file!(), &_args);
 
-    let parsed = parse_input(&input);
+    let parsed = parse_input(&input).unwrap_or_else(|e| panic!("{}", e));
     let result = part1(&parsed);
     println!("Part 1: {}", result);
     let result = part2(&parsed);
@@ -30,26 +25,27 @@ struct PageSet {
     pages: Vec<u32>,
 }
 
-fn parse_input(input: &str) -> (HashSet<Rule>, Vec<PageSet>) {
-    let rule_re = Regex::new(r"(\d+)\|(\d+)").unwrap();
-    let rules = input
-        .lines()
-        .map(|line| rule_re.captures(line))
-        .filter(|c| c.is_some())
-        .map(|c| c.unwrap())
-        .map(|c| Rule {
-            a: c[1].parse().unwrap(),
-            b: c[2].parse().unwrap(),
+fn parse_input(input: &str) -> Result<(HashSet<Rule>, Vec<PageSet>), ParseError> {
+    let blocks = parse::expect_blocks(input, 2)?;
+    let rules = blocks[0]
+        .lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| parse::pair(blocks[0].line_number(i), line, '|'))
+        .map(|pair| pair.map(|(a, b)| Rule { a, b }))
+        .collect::<Result<_, _>>()?;
+    let pagesets = blocks[1]
+        .lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            line.split(",")
+                .map(|s| parse::value(blocks[1].line_number(i), s))
+                .collect::<Result<_, _>>()
         })
-        .collect();
-    let pageset_re = Regex::new(r",").unwrap();
-    let pagesets = input
-        .lines()
-        .filter(|line| pageset_re.is_match(line))
-        .map(|line| line.split(",").map(|s| s.parse().unwrap()).collect())
-        .map(|pages| PageSet { pages })
-        .collect();
-    (rules, pagesets)
+        .map(|pages| pages.map(|pages| PageSet { pages }))
+        .collect::<Result<_, _>>()?;
+    Ok((rules, pagesets))
 }
 
 fn is_valid_pageset(pageset: &PageSet, rules: &HashSet<Rule>) -> bool {
@@ -1,18 +1,8 @@
-use std::{
-    collections::{HashMap, HashSet},
-    fs,
-};
-
-const DEFAULT_INPUT_FILE: &str = "input.txt";
+use crate::util::{cycle, grid, input, simulation::Simulation};
+use std::collections::HashMap;
 
 pub fn main(_args: Vec<String>) {
-    let this_file = file!();
-    let this_dir = std::path::Path::new(this_file).parent().unwrap();
-    let mut file_name = DEFAULT_INPUT_FILE;
-    if _args.len() >= 1 {
-        file_name = &_args[0];
-    }
-    let input = fs::read_to_string(this_dir.join(file_name)).unwrap();
+    let input = input::load(file!(), &_args);
 
     let parsed = parse_input_to_grid(&input);
     let result = part1(&parsed);
@@ -93,60 +83,77 @@ fn _add_obstacle(grid: &mut HashMap<(i32, i32), char>) {
     }
 }
 
-fn is_loop(grid: &mut HashMap<(i32, i32), char>) -> bool {
+fn next_guard_ijv(
+    grid: &HashMap<(i32, i32), char>,
+    guard_ijv: (i32, i32, char),
+) -> Option<(i32, i32, char)> {
+    let forward_guard_ij = get_forward_guard_ij(guard_ijv);
+    match grid.get(&forward_guard_ij) {
+        // Guard exits the grid
+        None => None,
+        // Guard encounters obstacle
+        Some('#') => Some((guard_ijv.0, guard_ijv.1, rotate_right(guard_ijv.2))),
+        // Guard moves forward
+        Some(_) => Some((forward_guard_ij.0, forward_guard_ij.1, guard_ijv.2)),
+    }
+}
+
+fn is_loop(grid: &HashMap<(i32, i32), char>) -> bool {
     let guard_ijv_result = find_guard_ijv(grid);
     if guard_ijv_result.is_err() {
         return false;
     }
     let guard_ijv = guard_ijv_result.unwrap();
-    let mut visited: HashSet<(i32, i32, char)> = HashSet::from([guard_ijv]);
-    while step(grid).is_ok() {
-        let guard_ijv_result = find_guard_ijv(grid);
-        if guard_ijv_result.is_err() {
-            return false;
-        }
-        let guard_ijv = guard_ijv_result.unwrap();
-        if visited.contains(&guard_ijv) {
-            return true;
-        }
-        visited.insert(guard_ijv);
-    }
-    false
+    cycle::find_cycle(guard_ijv, |&ijv| next_guard_ijv(grid, ijv)).is_some()
 }
 
 fn _print_grid(grid: &HashMap<(i32, i32), char>) {
-    let min_i = *grid.keys().map(|(i, _)| i).min().unwrap();
-    let max_i = *grid.keys().map(|(i, _)| i).max().unwrap();
-    let min_j = *grid.keys().map(|(_, j)| j).min().unwrap();
-    let max_j = *grid.keys().map(|(_, j)| j).max().unwrap();
-    for i in min_i..=max_i {
-        for j in min_j..=max_j {
-            print!("{}", grid.get(&(i, j)).unwrap_or(&' '));
-        }
-        println!();
+    print!("{}", grid::render_grid(grid, &[], grid::RenderMode::Plain));
+}
+
+#[derive(Clone)]
+struct Lab {
+    grid: HashMap<(i32, i32), char>,
+}
+
+impl Simulation for Lab {
+    type Snapshot = HashMap<(i32, i32), char>;
+
+    fn step(&mut self) -> bool {
+        step(&mut self.grid).is_ok()
+    }
+
+    fn snapshot(&self) -> Self::Snapshot {
+        self.grid.clone()
+    }
+
+    fn restore(&mut self, snapshot: Self::Snapshot) {
+        self.grid = snapshot;
     }
 }
 
-fn populate_xs(grid: &mut HashMap<(i32, i32), char>) {
-    while step(grid).is_ok() {}
+fn populate_xs(input: &HashMap<(i32, i32), char>) -> HashMap<(i32, i32), char> {
+    let mut lab = Lab {
+        grid: input.clone(),
+    };
+    lab.run_to_end();
+    lab.grid
 }
 
 fn part1(input: &HashMap<(i32, i32), char>) -> u32 {
-    let mut grid = input.clone();
-    populate_xs(&mut grid);
+    let grid = populate_xs(input);
     // _print_grid(&grid);
     grid.values().filter(|v| v == &&'X').count() as u32
 }
 
 fn part2(input: &HashMap<(i32, i32), char>) -> u32 {
-    let mut grid = input.clone();
-    populate_xs(&mut grid);
+    let grid = populate_xs(input);
     let mut loops = 0;
     let mut non_loops = 0;
     grid.keys().filter(|k| grid.get(k) == Some(&'X')).for_each(|k| {
         let mut grid2 = input.clone();
         grid2.insert(*k, '#');
-        match is_loop(&mut grid2) {
+        match is_loop(&grid2) {
             true => loops += 1,
             false => non_loops += 1,
         }
@@ -1,19 +1,12 @@
-use crate::util::grid;
-use std::{
-    collections::{HashMap, HashSet},
-    fs,
+use crate::util::{
+    grid, input,
+    prop::{Check, Property},
 };
-
-const DEFAULT_INPUT_FILE: &str = "input.txt";
+use rand::{rngs::StdRng, Rng};
+use std::collections::{HashMap, HashSet};
 
 pub fn main(_args: Vec<String>) {
-    let this_file = file!();
-    let this_dir = std::path::Path::new(this_file).parent().unwrap();
-    let mut file_name = DEFAULT_INPUT_FILE;
-    if _args.len() >= 1 {
-        file_name = &_args[0];
-    }
-    let input = fs::read_to_string(this_dir.join(file_name)).unwrap();
+    let input = input::load(file!(), &_args);
 
     let parsed = grid::parse_input_to_grid(&input);
     let result = part1(&parsed);
@@ -115,3 +108,105 @@ fn part2(input: &HashMap<(i32, i32), char>) -> i32 {
         .filter(|(i, j)| input.contains_key(&(*i, *j)))
         .count() as i32
 }
+
+// One frequency's antennas on a `height` x `width` map
+#[derive(Debug, Clone)]
+struct Antennas {
+    nodes: Vec<(i32, i32)>,
+    height: i32,
+    width: i32,
+}
+
+fn random_antennas(rng: &mut StdRng) -> Antennas {
+    let (height, width) = (rng.gen_range(1..20), rng.gen_range(1..20));
+    let mut nodes = vec![];
+    for _ in 0..rng.gen_range(0..6) {
+        let node = (rng.gen_range(0..height), rng.gen_range(0..width));
+        if !nodes.contains(&node) {
+            nodes.push(node);
+        }
+    }
+    Antennas {
+        nodes,
+        height,
+        width,
+    }
+}
+
+fn shrink_antennas(a: &Antennas) -> Vec<Antennas> {
+    let mut candidates = vec![];
+    for i in 0..a.nodes.len() {
+        let mut nodes = a.nodes.clone();
+        nodes.remove(i);
+        candidates.push(Antennas { nodes, ..*a });
+    }
+    let inside = |height: i32, width: i32| {
+        a.nodes
+            .iter()
+            .filter(|(i, j)| *i < height && *j < width)
+            .cloned()
+            .collect()
+    };
+    if a.height > 1 {
+        candidates.push(Antennas {
+            nodes: inside(a.height - 1, a.width),
+            height: a.height - 1,
+            width: a.width,
+        });
+    }
+    if a.width > 1 {
+        candidates.push(Antennas {
+            nodes: inside(a.height, a.width - 1),
+            height: a.height,
+            width: a.width - 1,
+        });
+    }
+    candidates
+}
+
+fn show_antennas(a: &Antennas) -> String {
+    (0..a.height)
+        .map(|i| {
+            (0..a.width)
+                .map(|j| if a.nodes.contains(&(i, j)) { 'a' } else { '.' })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Every in-bounds part 1 antinode is also a part 2 antinode, and so is every
+// antenna that shares its frequency with another one
+fn check_antinodes(a: &Antennas) -> Option<Result<(), String>> {
+    let nodes = a.nodes.iter().cloned().collect::<HashSet<_>>();
+    let v1 = calculate_antinodes_v1(&nodes);
+    let v2 = calculate_antinodes_v2(&nodes, 0, a.height - 1, 0, a.width - 1);
+    let mut expected = v1
+        .into_iter()
+        .filter(|(i, j)| (0..a.height).contains(i) && (0..a.width).contains(j))
+        .collect::<HashSet<_>>();
+    if nodes.len() >= 2 {
+        expected.extend(nodes.iter().cloned());
+    }
+    let mut missing = expected.difference(&v2).cloned().collect::<Vec<_>>();
+    missing.sort();
+    if missing.is_empty() {
+        Some(Ok(()))
+    } else {
+        Some(Err(format!(
+            "calculate_antinodes_v2 is missing {:?}",
+            missing
+        )))
+    }
+}
+
+pub fn properties() -> Vec<Box<dyn Check>> {
+    vec![Box::new(Property {
+        name: "day08-antinodes",
+        file: file!(),
+        generate: random_antennas,
+        shrink: shrink_antennas,
+        check: check_antinodes,
+        show: show_antennas,
+    })]
+}
@@ -1,15 +1,8 @@
-use std::{collections::HashMap, fs};
-
-const DEFAULT_INPUT_FILE: &str = "input.txt";
+use crate::util::{input, memo::Memo, simulation::Simulation};
+use std::collections::HashMap;
 
 pub fn main(_args: Vec<String>) {
-    let this_file = file!();
-    let this_dir = std::path::Path::new(this_file).parent().unwrap();
-    let mut file_name = DEFAULT_INPUT_FILE;
-    if _args.len() >= 1 {
-        file_name = &_args[0];
-    }
-    let input = fs::read_to_string(this_dir.join(file_name)).unwrap();
+    let input = input::load(file!(), &_args);
 
     let parsed = parse_input(&input);
     let result = part1(&parsed);
@@ -61,20 +54,71 @@ fn step(stones: &HashMap<Stone, u64>) -> HashMap<Stone, u64> {
     new_stones
 }
 
+struct Stones {
+    stones: HashMap<Stone, u64>,
+}
+
+impl Simulation for Stones {
+    type Snapshot = HashMap<Stone, u64>;
+
+    fn step(&mut self) -> bool {
+        self.stones = step(&self.stones);
+        true
+    }
+
+    fn snapshot(&self) -> Self::Snapshot {
+        self.stones.clone()
+    }
+
+    fn restore(&mut self, snapshot: Self::Snapshot) {
+        self.stones = snapshot;
+    }
+}
+
+// Blink by blink, for watching the stones change; the answers come from
+// count_stones
+#[allow(unused)]
 fn step_n_and_sum(input: &HashMap<Stone, u64>, n: u64) -> u64 {
-    let mut stones = input.clone();
-    for _ in 0..n {
-        stones = step(&stones);
+    let mut stones = Stones {
+        stones: input.clone(),
+    };
+    stones.run(n as usize);
+    stones.stones.values().map(|v| *v).sum::<u64>()
+}
+
+// number of stones a single stone turns into after `n` blinks
+fn count_stones(memo: &mut Memo<(u64, u64), u64>, val: u64, n: u64) -> u64 {
+    if n == 0 {
+        return 1;
     }
-    stones.values().map(|v| *v).sum::<u64>()
+    memo.get((val, n), |memo, _| {
+        let digits = val.to_string();
+        match (val, digits.len() % 2) {
+            (0, _) => count_stones(memo, 1, n - 1),
+            (_, 0) => {
+                let l = digits[0..digits.len() / 2].parse::<u64>().unwrap();
+                let r = digits[digits.len() / 2..].parse::<u64>().unwrap();
+                count_stones(memo, l, n - 1) + count_stones(memo, r, n - 1)
+            }
+            _ => count_stones(memo, val * 2024, n - 1),
+        }
+    })
+}
+
+fn count_n_and_sum(input: &HashMap<Stone, u64>, n: u64) -> u64 {
+    let mut memo = Memo::new();
+    input
+        .iter()
+        .map(|(stone, count)| count * count_stones(&mut memo, stone.val, n))
+        .sum()
 }
 
 #[allow(unused)]
 fn part1(input: &HashMap<Stone, u64>) -> u64 {
-    step_n_and_sum(input, 25)
+    count_n_and_sum(input, 25)
 }
 
 #[allow(unused)]
 fn part2(input: &HashMap<Stone, u64>) -> u64 {
-    step_n_and_sum(input, 75)
+    count_n_and_sum(input, 75)
 }
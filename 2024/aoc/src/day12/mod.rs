@@ -1,19 +1,14 @@
-use crate::util::grid;
-use std::{
-    collections::{HashMap, HashSet},
-    fs,
+use crate::{
+    repl::{self, Session},
+    util::{
+        grid::{self, Colour, Overlay, RenderMode},
+        input,
+    },
 };
-
-const DEFAULT_INPUT_FILE: &str = "input.txt";
+use std::collections::{HashMap, HashSet};
 
 pub fn main(_args: Vec<String>) {
-    let this_file = file!();
-    let this_dir = std::path::Path::new(this_file).parent().unwrap();
-    let mut file_name = DEFAULT_INPUT_FILE;
-    if _args.len() >= 1 {
-        file_name = &_args[0];
-    }
-    let input = fs::read_to_string(this_dir.join(file_name)).unwrap();
+    let input = input::load(file!(), &_args);
 
     let parsed = grid::parse_input_to_grid(&input);
     let result = part1(&parsed);
@@ -23,9 +18,14 @@ pub fn main(_args: Vec<String>) {
 }
 
 fn identify_region(grid: &mut HashMap<(i32, i32), char>) -> HashSet<(i32, i32)> {
-    let ((i, j), c) = grid.iter().next().unwrap().clone();
-    let mut region: HashSet<(i32, i32)> = HashSet::from([(*i, *j)]);
-    let mut search: HashSet<(i32, i32)> = HashSet::from([(*i, *j)]);
+    let start = *grid.keys().next().unwrap();
+    region_at(grid, start)
+}
+
+fn region_at(grid: &HashMap<(i32, i32), char>, (i, j): (i32, i32)) -> HashSet<(i32, i32)> {
+    let c = &grid[&(i, j)];
+    let mut region: HashSet<(i32, i32)> = HashSet::from([(i, j)]);
+    let mut search: HashSet<(i32, i32)> = HashSet::from([(i, j)]);
     while !search.is_empty() {
         let (i, j) = *search.iter().next().unwrap();
         search.remove(&(i, j));
@@ -123,3 +123,57 @@ fn part2(input: &HashMap<(i32, i32), char>) -> i32 {
     }
     price
 }
+
+struct ReplSession {
+    input: HashMap<(i32, i32), char>,
+}
+
+impl Session for ReplSession {
+    fn part1(&self) -> String {
+        part1(&self.input).to_string()
+    }
+
+    fn part2(&self) -> String {
+        part2(&self.input).to_string()
+    }
+
+    fn commands(&self) -> Vec<(&'static str, &'static str)> {
+        vec![("region <i> <j>", "show the region containing plot (i, j)")]
+    }
+
+    fn run(&mut self, command: &str, args: &[&str]) -> Option<Result<String, String>> {
+        match command {
+            "region" => Some(self.region(args)),
+            _ => None,
+        }
+    }
+}
+
+impl ReplSession {
+    fn region(&self, args: &[&str]) -> Result<String, String> {
+        let p = (repl::arg(args, 0, "i")?, repl::arg(args, 1, "j")?);
+        if !self.input.contains_key(&p) {
+            return Err(format!("{:?} is outside the garden", p));
+        }
+        let region = region_at(&self.input, p);
+        let overlay = Overlay::new(region.iter().cloned()).with_colour(Colour::Green);
+        Ok(format!(
+            "{}plant {}, area {}, perimeter {}, sides {}, price {}, discounted price {}",
+            grid::render_grid(&self.input, &[overlay], RenderMode::Ansi),
+            self.input[&p],
+            get_region_area(&region),
+            get_region_perimeter(&region),
+            get_region_sides(&region),
+            get_region_price(&region),
+            get_region_discounted_price(&region)
+        ))
+    }
+}
+
+pub fn session(_args: Vec<String>) -> Box<dyn Session> {
+    let input = input::load(file!(), &_args);
+
+    Box::new(ReplSession {
+        input: grid::parse_input_to_grid(&input),
+    })
+}
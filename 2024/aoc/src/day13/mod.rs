@@ -1,16 +1,13 @@
-use regex::Regex;
-use std::fs;
-
-const DEFAULT_INPUT_FILE: &str = "input.txt";
+use crate::util::{
+    input,
+    math::{self, LinearSolution},
+    parse::{self, Block, ParseError},
+    prop::{self, Check, Property},
+};
+use rand::{rngs::StdRng, Rng};
 
 pub fn main(_args: Vec<String>) {
-    let this_file = file!();
-    let this_dir = std::path::Path::new(this_file).parent().unwrap();
-    let mut file_name = DEFAULT_INPUT_FILE;
-    if _args.len() >= 1 {
-        file_name = &_args[0];
-    }
-    let input = fs::read_to_string(this_dir.join(file_name)).unwrap();
+    let input = input::load(file!(), &_args);
 
     let parsed = parse_input(&input);
     let result = part1(&parsed);
@@ -37,35 +34,27 @@ fn move_prize(machine: &Machine) -> Machine {
     }
 }
 
-fn parse_input_to_coordinate(input: String) -> (i64, i64) {
-    let re = Regex::new(r"X.(\d+), Y.(\d+)").unwrap();
-    // println!("{}", input);
-    let captures = re.captures(&input).unwrap();
-    (
-        captures[1].parse::<i64>().unwrap(),
-        captures[2].parse::<i64>().unwrap(),
-    )
+fn parse_input_to_coordinate(block: &Block, i: usize) -> Result<(i64, i64), ParseError> {
+    match parse::ints(block.line_number(i), block.lines[i])?[..] {
+        [x, y] => Ok((x, y)),
+        _ => Err(block.error(i, "expected an X and a Y value")),
+    }
 }
 
-fn parse_input_to_machine(input: (String, String, String)) -> Machine {
-    Machine {
-        a: parse_input_to_coordinate(input.0),
-        b: parse_input_to_coordinate(input.1),
-        prize: parse_input_to_coordinate(input.2),
-    }
+fn parse_input_to_machine(block: &Block) -> Result<Machine, ParseError> {
+    block.expect_len(3)?;
+    Ok(Machine {
+        a: parse_input_to_coordinate(block, 0)?,
+        b: parse_input_to_coordinate(block, 1)?,
+        prize: parse_input_to_coordinate(block, 2)?,
+    })
 }
 
 fn parse_input(input: &str) -> Vec<Machine> {
-    let mut machines = vec![];
-    for (i, _) in input.lines().enumerate().step_by(4) {
-        let machine = parse_input_to_machine((
-            input.lines().nth(i).unwrap().to_string(),
-            input.lines().nth(i + 1).unwrap().to_string(),
-            input.lines().nth(i + 2).unwrap().to_string(),
-        ));
-        machines.push(machine);
-    }
-    machines
+    parse::blocks(input)
+        .iter()
+        .map(|block| parse_input_to_machine(block).unwrap())
+        .collect()
 }
 
 fn calculate_cost(machine: &Machine) -> Option<i64> {
@@ -91,6 +80,10 @@ fn calculate_cost(machine: &Machine) -> Option<i64> {
             return Some(3 * a_count + b_count);
         }
         if pos.0 >= prize.0 || pos.1 >= prize.1 {
+            // overshot with no B presses left to take back
+            if b_count == 0 {
+                break;
+            }
             b_count -= 1;
             pos.0 -= b.0;
             pos.1 -= b.1;
@@ -99,9 +92,6 @@ fn calculate_cost(machine: &Machine) -> Option<i64> {
             pos.0 += a.0;
             pos.1 += a.1;
         }
-        if b_count == 0 {
-            break;
-        }
     }
     None
 }
@@ -126,24 +116,63 @@ fn calculate_cost_efficient(machine: &Machine) -> Option<i64> {
     let (bx, by) = machine.b;
     let (tx, ty) = machine.prize;
 
-    let n_a = (bx * ty - by * tx) as f64 / (ay * bx - ax * by) as f64;
-    let n_b = (ax * ty - ay * tx) as f64 / (ax * by - ay * bx) as f64;
+    match math::solve_linear(&[vec![ax, bx], vec![ay, by]], &[tx, ty]) {
+        LinearSolution::None => None,
+        LinearSolution::Unique(n) => {
+            let n_a = n[0].to_integer()?;
+            let n_b = n[1].to_integer()?;
+            if n_a < 0 || n_b < 0 {
+                return None;
+            }
+            Some(3 * n_a + n_b)
+        }
+        LinearSolution::Infinite { .. } => {
+            // colinear buttons: both rows describe the same line, so one is enough
+            if (ax, bx) != (0, 0) {
+                calculate_cost_colinear(ax, bx, tx)
+            } else {
+                calculate_cost_colinear(ay, by, ty)
+            }
+        }
+    }
+}
 
-    if n_a == (n_a as i64) as f64 && n_b == (n_b as i64) as f64 {
-        return Some(3 * n_a as i64 + n_b as i64);
+// cheapest n_a, n_b >= 0 with a * n_a + b * n_b == t
+fn calculate_cost_colinear(a: i64, b: i64, t: i64) -> Option<i64> {
+    if (a, b) == (0, 0) {
+        return if t == 0 { Some(0) } else { None };
     }
-    None
+    let ((n_a, n_b), (d_a, d_b)) = math::solve_diophantine(a, b, t)?;
+    // in i128, so part 2's offsets times the step can't overflow
+    let (n_a, n_b, d_a, d_b) = (n_a as i128, n_b as i128, d_a as i128, d_b as i128);
+    // n_a + k * d_a >= 0 and n_b + k * d_b >= 0 bound k from one side each
+    let mut k_min = i128::MIN;
+    let mut k_max = i128::MAX;
+    for (n, d) in [(n_a, d_a), (n_b, d_b)] {
+        match d.signum() {
+            1 => k_min = k_min.max((-n).div_euclid(d) + ((-n).rem_euclid(d) != 0) as i128),
+            -1 => k_max = k_max.min(n.div_euclid(-d)),
+            _ if n < 0 => return None,
+            _ => {}
+        }
+    }
+    if k_min > k_max {
+        return None;
+    }
+    let slope = 3 * d_a + d_b;
+    let k = match slope.signum() {
+        1 => k_min,
+        -1 => k_max,
+        _ => k_min.max(k_max.min(0)),
+    };
+    if k == i128::MIN || k == i128::MAX {
+        return None;
+    }
+    i64::try_from(3 * (n_a + k * d_a) + (n_b + k * d_b)).ok()
 }
 
 #[allow(unused)]
 fn part2(input: &Vec<Machine>) -> i64 {
-    let machines: Vec<Machine> = input.iter().map(|m| move_prize(m)).collect();
-    for machine in machines.iter() {
-        if machine.a.0 * machine.b.1 == machine.a.1 * machine.b.0 {
-            // Proving there are no machines with colinear buttons; thus a solution is unique
-            println!("a and b buttons are aligned -- {:?}", machine);
-        }
-    }
     input
         .iter()
         .map(|m| move_prize(m))
@@ -152,3 +181,100 @@ fn part2(input: &Vec<Machine>) -> i64 {
         .map(|c| c.unwrap())
         .sum()
 }
+
+fn show_machine(m: &Machine) -> String {
+    format!(
+        "Button A: X+{}, Y+{}\nButton B: X+{}, Y+{}\nPrize: X={}, Y={}",
+        m.a.0, m.a.1, m.b.0, m.b.1, m.prize.0, m.prize.1
+    )
+}
+
+// Small machines, so the step-by-step search stays quick
+fn random_machine(rng: &mut StdRng) -> Machine {
+    let a = (rng.gen_range(1..30), rng.gen_range(1..30));
+    let b = (rng.gen_range(1..30), rng.gen_range(1..30));
+    let prize = if rng.gen_bool(0.5) {
+        let (n_a, n_b) = (rng.gen_range(0..40), rng.gen_range(0..40));
+        (a.0 * n_a + b.0 * n_b, a.1 * n_a + b.1 * n_b)
+    } else {
+        (rng.gen_range(0..1000), rng.gen_range(0..1000))
+    };
+    Machine { a, b, prize }
+}
+
+fn shrink_machine(m: &Machine) -> Vec<Machine> {
+    let mut candidates = vec![];
+    // one press fewer keeps a reachable prize reachable
+    for button in [m.a, m.b] {
+        let prize = (m.prize.0 - button.0, m.prize.1 - button.1);
+        if prize.0 >= 0 && prize.1 >= 0 {
+            candidates.push(Machine { prize, ..*m });
+        }
+    }
+    for x in prop::shrink_int(m.prize.0, 0) {
+        candidates.push(Machine {
+            prize: (x, m.prize.1),
+            ..*m
+        });
+    }
+    for y in prop::shrink_int(m.prize.1, 0) {
+        candidates.push(Machine {
+            prize: (m.prize.0, y),
+            ..*m
+        });
+    }
+    for v in prop::shrink_int(m.a.0, 1) {
+        candidates.push(Machine {
+            a: (v, m.a.1),
+            ..*m
+        });
+    }
+    for v in prop::shrink_int(m.a.1, 1) {
+        candidates.push(Machine {
+            a: (m.a.0, v),
+            ..*m
+        });
+    }
+    for v in prop::shrink_int(m.b.0, 1) {
+        candidates.push(Machine {
+            b: (v, m.b.1),
+            ..*m
+        });
+    }
+    for v in prop::shrink_int(m.b.1, 1) {
+        candidates.push(Machine {
+            b: (m.b.0, v),
+            ..*m
+        });
+    }
+    candidates
+}
+
+// With colinear buttons the step-by-step search settles for the most B
+// presses, which need not be the cheapest, so only compare the unique case
+fn check_cost(m: &Machine) -> Option<Result<(), String>> {
+    if m.a.0 * m.b.1 == m.a.1 * m.b.0 {
+        return None;
+    }
+    let naive = calculate_cost(m);
+    let efficient = calculate_cost_efficient(m);
+    if naive == efficient {
+        Some(Ok(()))
+    } else {
+        Some(Err(format!(
+            "calculate_cost gave {:?}, calculate_cost_efficient gave {:?}",
+            naive, efficient
+        )))
+    }
+}
+
+pub fn properties() -> Vec<Box<dyn Check>> {
+    vec![Box::new(Property {
+        name: "day13-cost",
+        file: file!(),
+        generate: random_machine,
+        shrink: shrink_machine,
+        check: check_cost,
+        show: show_machine,
+    })]
+}
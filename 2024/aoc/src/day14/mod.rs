@@ -1,18 +1,20 @@
+use crate::util::{
+    input, math,
+    parse::{self, ParseError},
+    simulation::Simulation,
+    torus::{self, Torus},
+};
 use regex::Regex;
-use std::{collections::HashMap, fs};
-
-const DEFAULT_INPUT_FILE: &str = "input.txt";
+use std::collections::HashMap;
 
 pub fn main(_args: Vec<String>) {
-    let this_file = file!();
-    let this_dir = std::path::Path::new(this_file).parent().unwrap();
-    let mut file_name = DEFAULT_INPUT_FILE;
-    if _args.len() >= 1 {
-        file_name = &_args[0];
-    }
-    let input = fs::read_to_string(this_dir.join(file_name)).unwrap();
+    let input = input::load(file!(), &_args);
 
-    let parsed = parse_input(&input);
+    let mut parsed = parse_input(&input).unwrap_or_else(|e| panic!("{}", e));
+    if _args.len() >= 2 {
+        parsed.1 = parse_room_size(0, &_args[1])
+            .unwrap_or_else(|e| panic!("room size argument: {}", e.message));
+    }
     let result = part1(&parsed);
     println!("Part 1: {}", result);
     let result = part2(&parsed);
@@ -25,55 +27,87 @@ struct Robot {
     v: (i64, i64),
 }
 
-fn parse_input_to_robot(input: String) -> Robot {
+fn parse_input_to_robot(line_no: usize, line: &str) -> Result<Robot, ParseError> {
     let re = Regex::new(r"p=(-?\d+),(-?\d+) v=(-?\d+),(-?\d+)").unwrap();
-    let captures = re.captures(&input).unwrap();
-    Robot {
-        p: (
-            captures[1].parse::<i64>().unwrap(),
-            captures[2].parse::<i64>().unwrap(),
-        ),
-        v: (
-            captures[3].parse::<i64>().unwrap(),
-            captures[4].parse::<i64>().unwrap(),
-        ),
+    let captures = parse::captures(line_no, &re, line)?;
+    let n = |i: usize| parse::value::<i64>(line_no, captures[i]);
+    Ok(Robot {
+        p: (n(0)?, n(1)?),
+        v: (n(2)?, n(3)?),
+    })
+}
+
+fn parse_room_size(line_no: usize, input: &str) -> Result<Torus, ParseError> {
+    // e.g. "11x7" or "11,7"
+    let re = Regex::new(r"^(\d+)[x,](\d+)$").unwrap();
+    let captures = parse::captures(line_no, &re, input.trim())?;
+    let width = parse::value::<i64>(line_no, captures[0])?;
+    let height = parse::value::<i64>(line_no, captures[1])?;
+    if width == 0 || height == 0 {
+        return Err(ParseError::new(line_no, "the room can't be empty"));
     }
+    Ok(Torus::new(width, height))
 }
 
-fn parse_input(input: &str) -> Vec<Robot> {
+// Robots in a 101x103 room, unless a "size=W,H" line says otherwise
+fn parse_input(input: &str) -> Result<(Vec<Robot>, Torus), ParseError> {
     let mut robots = vec![];
-    for line in input.lines() {
-        let robot = parse_input_to_robot(line.to_string());
-        robots.push(robot);
+    let mut room_size = Torus::new(101, 103);
+    for (line_no, line) in parse::lines(input) {
+        if let Some(size) = line.strip_prefix("size=") {
+            room_size = parse_room_size(line_no, size)?;
+            continue;
+        }
+        robots.push(parse_input_to_robot(line_no, line)?);
     }
-    robots
+    Ok((robots, room_size))
 }
 
-fn get_room_size(robots: &Vec<Robot>) -> (i64, i64) {
-    if robots.len() == 12 {
-        return (11, 7);
-    }
-    (101, 103)
+fn move_n_robot(robot: &mut Robot, n: i64, room_size: Torus) -> () {
+    robot.p = room_size.position_after(robot.p, robot.v, n);
 }
 
-fn move_robot(robot: &mut Robot, room_size: (i64, i64)) -> () {
-    let new_x = (robot.p.0 + robot.v.0 + room_size.0) % room_size.0;
-    let new_y = (robot.p.1 + robot.v.1 + room_size.1) % room_size.1;
-    robot.p = (new_x, new_y);
+struct Room {
+    robots: Vec<Robot>,
+    room_size: Torus,
 }
 
-fn move_n_robot(robot: &mut Robot, n: i64, room_size: (i64, i64)) -> () {
-    for _ in 0..n {
-        move_robot(robot, room_size);
+impl Simulation for Room {
+    type Snapshot = Vec<Robot>;
+
+    // An empty room has nothing to move; otherwise the robots never stop
+    fn step(&mut self) -> bool {
+        self.run(1) == 1
+    }
+
+    fn snapshot(&self) -> Self::Snapshot {
+        self.robots.clone()
+    }
+
+    fn restore(&mut self, snapshot: Self::Snapshot) {
+        self.robots = snapshot;
+    }
+
+    // positions after n steps have a closed form, so skip the stepping. They
+    // repeat every width * height steps, which keeps any n in range of an i64.
+    fn run(&mut self, n: usize) -> usize {
+        if self.robots.is_empty() {
+            return 0;
+        }
+        let n_mod = (n % self.room_size.area() as usize) as i64;
+        for robot in self.robots.iter_mut() {
+            move_n_robot(robot, n_mod, self.room_size);
+        }
+        n
     }
 }
 
-fn calc_quadrant(robot: &Robot, room_size: (i64, i64)) -> char {
+fn calc_quadrant(robot: &Robot, room_size: Torus) -> char {
     // 'A', 'B', 'C', 'D'
-    let w = robot.p.0 < (room_size.0 - 1) / 2;
-    let e = robot.p.0 > (room_size.0 - 1) / 2;
-    let n = robot.p.1 < (room_size.1 - 1) / 2;
-    let s = robot.p.1 > (room_size.1 - 1) / 2;
+    let w = robot.p.0 < (room_size.width - 1) / 2;
+    let e = robot.p.0 > (room_size.width - 1) / 2;
+    let n = robot.p.1 < (room_size.height - 1) / 2;
+    let s = robot.p.1 > (room_size.height - 1) / 2;
     match (w, e, n, s) {
         (true, false, true, false) => 'A',
         (true, false, false, true) => 'B',
@@ -83,7 +117,7 @@ fn calc_quadrant(robot: &Robot, room_size: (i64, i64)) -> char {
     }
 }
 
-fn calc_quadrant_map(robots: &Vec<Robot>, room_size: (i64, i64)) -> HashMap<char, Vec<Robot>> {
+fn calc_quadrant_map(robots: &Vec<Robot>, room_size: Torus) -> HashMap<char, Vec<Robot>> {
     let mut map = HashMap::new();
     for robot in robots {
         let quadrant = calc_quadrant(robot, room_size);
@@ -94,14 +128,13 @@ fn calc_quadrant_map(robots: &Vec<Robot>, room_size: (i64, i64)) -> HashMap<char
     map
 }
 
-fn print_robots(robots: &Vec<Robot>) {
-    let room_size = get_room_size(robots);
+fn print_robots(robots: &Vec<Robot>, room_size: Torus) {
     let mut map = HashMap::new();
     robots.iter().map(|r| r.p).for_each(|p| {
         *map.entry(p).or_insert(0) += 1;
     });
-    for y in 0..room_size.1 {
-        for x in 0..room_size.0 {
+    for y in 0..room_size.height {
+        for x in 0..room_size.width {
             if map.contains_key(&(x, y)) {
                 print!("{}", map[&(x, y)]);
             } else {
@@ -112,43 +145,67 @@ fn print_robots(robots: &Vec<Robot>) {
     }
 }
 
-fn count_coherence(robots: &Vec<Robot>) -> f32 {
-    let mut score = 0.0;
-    for robot in robots {
-        for other in robots {
-            if (robot.p.0 - other.p.0).abs() <= 2 && (robot.p.1 - other.p.1).abs() <= 2 {
-                score += 1.0;
-            }
-        }
-    }
-    score / (robots.len() as f32)
+fn calc_spread(values: &[i64]) -> i64 {
+    // n^2 times the variance
+    let n = values.len() as i64;
+    let sum: i64 = values.iter().sum();
+    let sum_sq: i64 = values.iter().map(|v| v * v).sum();
+    n * sum_sq - sum * sum
+}
+
+fn most_clustered_step(robots: &[Robot], size: i64, axis: impl Fn((i64, i64)) -> i64) -> i64 {
+    (0..size)
+        .min_by_key(|&n| {
+            let values = robots
+                .iter()
+                .map(|r| torus::wrap_after(axis(r.p), axis(r.v), n, size))
+                .collect::<Vec<i64>>();
+            calc_spread(&values)
+        })
+        .unwrap()
+}
+
+// The step in one full period where both axes are most clustered at once
+fn most_clustered_step_2d(robots: &[Robot], room_size: Torus) -> i64 {
+    (0..math::lcm(room_size.width, room_size.height))
+        .min_by_key(|&n| {
+            let (xs, ys): (Vec<i64>, Vec<i64>) = robots
+                .iter()
+                .map(|r| room_size.position_after(r.p, r.v, n))
+                .unzip();
+            calc_spread(&xs) + calc_spread(&ys)
+        })
+        .unwrap()
 }
 
 #[allow(unused)]
-fn part1(input: &Vec<Robot>) -> i64 {
-    let mut robots = input.iter().map(|r| r.clone()).collect::<Vec<Robot>>();
-    let room_size = get_room_size(input);
-    for robot in robots.iter_mut() {
-        move_n_robot(robot, 100, room_size);
-    }
-    let qm = calc_quadrant_map(&robots, room_size);
+fn part1((input, room_size): &(Vec<Robot>, Torus)) -> i64 {
+    let mut room = Room {
+        robots: input.clone(),
+        room_size: *room_size,
+    };
+    room.run(100);
+    let qm = calc_quadrant_map(&room.robots, room.room_size);
     qm.values().map(|v| v.len()).product::<usize>() as i64
 }
 
 #[allow(unused)]
-fn part2(input: &Vec<Robot>) -> i64 {
-    let mut n = 0;
-    let mut robots = input.iter().map(|r| r.clone()).collect::<Vec<Robot>>();
-    let room_size = get_room_size(input);
-    for i in 0..(101 * 103) {
-        robots.iter_mut().for_each(|r| move_robot(r, room_size));
-        let coherence = count_coherence(&robots);
-        if coherence > 5.0 {
-            // println!("  -- {} {}", i, coherence);
-            n = i + 1;
-            break;
-        }
-    }
-    print_robots(&robots);
+fn part2((input, room_size): &(Vec<Robot>, Torus)) -> i64 {
+    let room_size = *room_size;
+    // x repeats every `width` steps and y every `height` steps, so find the
+    // step where each axis is most clustered and combine them. If the sides
+    // share a factor the two steps may not line up, so scan instead.
+    let n_x = most_clustered_step(input, room_size.width, |p| p.0);
+    let n_y = most_clustered_step(input, room_size.height, |p| p.1);
+    let n = match math::crt(&[(n_x, room_size.width), (n_y, room_size.height)]) {
+        Some((n, _)) => n,
+        None => most_clustered_step_2d(input, room_size),
+    };
+    let mut room = Room {
+        robots: input.clone(),
+        room_size,
+    };
+    room.run(n as usize);
+    print_robots(&room.robots, room_size);
     n
 }
@@ -1,19 +1,14 @@
-use crate::util::grid;
-use std::{
-    collections::{HashMap, HashSet},
-    fs,
+use crate::util::{
+    grid::{self, RenderMode},
+    input, parse,
+    prop::{Check, Property},
+    simulation::Simulation,
 };
-
-const DEFAULT_INPUT_FILE: &str = "input.txt";
+use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+use std::collections::{HashMap, HashSet};
 
 pub fn main(_args: Vec<String>) {
-    let this_file = file!();
-    let this_dir = std::path::Path::new(this_file).parent().unwrap();
-    let mut file_name = DEFAULT_INPUT_FILE;
-    if _args.len() >= 1 {
-        file_name = &_args[0];
-    }
-    let input = fs::read_to_string(this_dir.join(file_name)).unwrap();
+    let input = input::load(file!(), &_args);
 
     let (grid, moves) = parse_input(&input);
     let result = part1(&grid, &moves);
@@ -30,15 +25,15 @@ struct Move {
 fn parse_input(input: &str) -> (HashMap<(i32, i32), char>, Vec<Move>) {
     let mut grid = HashMap::new();
     let mut moves = Vec::new();
-    input.lines().enumerate().for_each(|(i, line)| {
-        if line.starts_with("#") {
-            line.chars().enumerate().for_each(|(j, c)| {
-                grid.insert((i as i32, j as i32), c);
-            });
-        } else {
-            for c in line.trim().chars() {
-                moves.push(Move { direction: c });
-            }
+    let blocks = parse::blocks(input);
+    blocks[0].lines.iter().enumerate().for_each(|(i, line)| {
+        line.chars().enumerate().for_each(|(j, c)| {
+            grid.insert((i as i32, j as i32), c);
+        });
+    });
+    blocks[1].lines.iter().for_each(|line| {
+        for c in line.trim().chars() {
+            moves.push(Move { direction: c });
         }
     });
     (grid, moves)
@@ -140,6 +135,52 @@ fn move_grid_2(grid: &mut HashMap<(i32, i32), char>, pos: (i32, i32), m: &Move)
     move_grid(grid, pos, m)
 }
 
+struct Warehouse<'a> {
+    grid: HashMap<(i32, i32), char>,
+    pos: (i32, i32),
+    moves: &'a [Move],
+    next_move: usize,
+    wide: bool,
+}
+
+impl<'a> Warehouse<'a> {
+    fn new(grid: HashMap<(i32, i32), char>, moves: &'a [Move], wide: bool) -> Self {
+        let pos = find_robot_pos(&grid);
+        Self {
+            grid,
+            pos,
+            moves,
+            next_move: 0,
+            wide,
+        }
+    }
+}
+
+impl Simulation for Warehouse<'_> {
+    type Snapshot = (HashMap<(i32, i32), char>, (i32, i32), usize);
+
+    fn step(&mut self) -> bool {
+        let Some(m) = self.moves.get(self.next_move) else {
+            return false;
+        };
+        self.pos = if self.wide {
+            move_grid_2(&mut self.grid, self.pos, m)
+        } else {
+            move_grid(&mut self.grid, self.pos, m)
+        };
+        self.next_move += 1;
+        true
+    }
+
+    fn snapshot(&self) -> Self::Snapshot {
+        (self.grid.clone(), self.pos, self.next_move)
+    }
+
+    fn restore(&mut self, snapshot: Self::Snapshot) {
+        (self.grid, self.pos, self.next_move) = snapshot;
+    }
+}
+
 fn gps_score(grid: &HashMap<(i32, i32), char>, c: char) -> i32 {
     grid.iter()
         .filter(|(_, &c2)| c2 == c)
@@ -150,17 +191,10 @@ fn gps_score(grid: &HashMap<(i32, i32), char>, c: char) -> i32 {
 
 #[allow(unused)]
 fn part1(grid: &HashMap<(i32, i32), char>, moves: &Vec<Move>) -> i32 {
-    let mut grid = grid.clone();
-    let mut pos = find_robot_pos(&grid);
-    // pos = move_grid(&mut grid, pos, &Move { direction: '>' });
-    moves.iter().for_each(|m| {
-        // grid::print_grid(&grid);
-        // println!("{:?}", pos);
-        // println!("{:?}", m);
-        pos = move_grid(&mut grid, pos, m);
-    });
-    // grid::print_grid(&grid);
-    gps_score(&grid, 'O')
+    let mut warehouse = Warehouse::new(grid.clone(), moves, false);
+    warehouse.run_to_end();
+    // grid::print_grid(&warehouse.grid);
+    gps_score(&warehouse.grid, 'O')
 }
 
 fn expand_grid(grid: &HashMap<(i32, i32), char>) -> HashMap<(i32, i32), char> {
@@ -186,12 +220,257 @@ fn expand_grid(grid: &HashMap<(i32, i32), char>) -> HashMap<(i32, i32), char> {
 
 #[allow(unused)]
 fn part2(grid: &HashMap<(i32, i32), char>, moves: &Vec<Move>) -> i32 {
-    let mut grid = expand_grid(grid);
-    let mut pos = find_robot_pos(&grid);
-    moves.iter().for_each(|m| {
-        pos = move_grid_2(&mut grid, pos, m);
-    });
-    grid::print_grid(&grid);
-    // println!("{:?}", pos);
-    gps_score(&grid, '[')
+    let mut warehouse = Warehouse::new(expand_grid(grid), moves, true);
+    warehouse.run_to_end();
+    grid::print_grid(&warehouse.grid);
+    gps_score(&warehouse.grid, '[')
+}
+
+// A narrow warehouse and moves to make in it, in all four directions
+#[derive(Debug, Clone)]
+struct WarehouseMoves {
+    grid: HashMap<(i32, i32), char>,
+    moves: Vec<char>,
+}
+
+fn random_warehouse_moves(rng: &mut StdRng) -> WarehouseMoves {
+    let (height, width) = (rng.gen_range(3..8), rng.gen_range(3..8));
+    let mut grid = HashMap::new();
+    for i in 0..height {
+        for j in 0..width {
+            let border = i == 0 || j == 0 || i == height - 1 || j == width - 1;
+            let c = match rng.gen_range(0..10) {
+                _ if border => '#',
+                0 => '#',
+                1..=4 => 'O',
+                _ => '.',
+            };
+            grid.insert((i, j), c);
+        }
+    }
+    let robot = (rng.gen_range(1..height - 1), rng.gen_range(1..width - 1));
+    grid.insert(robot, '@');
+    let moves = (0..rng.gen_range(1..40))
+        .map(|_| *['<', '>', '^', 'v'].choose(rng).unwrap())
+        .collect();
+    WarehouseMoves { grid, moves }
+}
+
+fn shrink_warehouse_moves(w: &WarehouseMoves) -> Vec<WarehouseMoves> {
+    let mut candidates = vec![];
+    for i in 0..w.moves.len() {
+        let mut moves = w.moves.clone();
+        moves.remove(i);
+        candidates.push(WarehouseMoves {
+            grid: w.grid.clone(),
+            moves,
+        });
+    }
+    let mut cells = w.grid.keys().cloned().collect::<Vec<_>>();
+    cells.sort();
+    for (i, j) in cells {
+        let inner = [(i - 1, j), (i + 1, j), (i, j - 1), (i, j + 1)]
+            .iter()
+            .all(|p| w.grid.contains_key(p));
+        match w.grid[&(i, j)] {
+            'O' => {}
+            '#' if inner => {}
+            _ => continue,
+        }
+        let mut grid = w.grid.clone();
+        grid.insert((i, j), '.');
+        candidates.push(WarehouseMoves {
+            grid,
+            moves: w.moves.clone(),
+        });
+    }
+    candidates
+}
+
+fn show_warehouse_moves(w: &WarehouseMoves) -> String {
+    format!(
+        "{}\n{}\n",
+        grid::render_grid(&w.grid, &[], RenderMode::Plain),
+        w.moves.iter().collect::<String>()
+    )
+}
+
+// The wide warehouse as whole boxes, each kept by its left half, so pushing
+// is a search over boxes rather than over cells
+struct WideBoxes {
+    walls: HashSet<(i32, i32)>,
+    boxes: HashSet<(i32, i32)>,
+    robot: (i32, i32),
+}
+
+impl WideBoxes {
+    fn new(grid: &HashMap<(i32, i32), char>) -> Self {
+        let cells = |c: char| grid.iter().filter(move |(_, &c2)| c2 == c).map(|(p, _)| *p);
+        WideBoxes {
+            walls: cells('#').collect(),
+            boxes: cells('[').collect(),
+            robot: find_robot_pos(grid),
+        }
+    }
+
+    fn box_at(&self, (i, j): (i32, i32)) -> Option<(i32, i32)> {
+        [(i, j), (i, j - 1)]
+            .into_iter()
+            .find(|b| self.boxes.contains(b))
+    }
+
+    fn push(&mut self, direction: char) {
+        let dir = match direction {
+            '>' => (0, 1),
+            '<' => (0, -1),
+            '^' => (-1, 0),
+            _ => (1, 0),
+        };
+        let step = |(i, j): (i32, i32)| (i + dir.0, j + dir.1);
+        let mut pushed = HashSet::new();
+        let mut scan = vec![step(self.robot)];
+        while let Some(p) = scan.pop() {
+            if self.walls.contains(&p) {
+                return;
+            }
+            if let Some(b) = self.box_at(p) {
+                if pushed.insert(b) {
+                    scan.extend([step(b), step((b.0, b.1 + 1))]);
+                }
+            }
+        }
+        self.boxes = self
+            .boxes
+            .iter()
+            .map(|&b| if pushed.contains(&b) { step(b) } else { b })
+            .collect();
+        self.robot = step(self.robot);
+    }
+
+    fn render(&self, cells: impl Iterator<Item = (i32, i32)>) -> HashMap<(i32, i32), char> {
+        cells
+            .map(|p| {
+                let c = match self.box_at(p) {
+                    _ if self.walls.contains(&p) => '#',
+                    _ if p == self.robot => '@',
+                    Some(b) if b == p => '[',
+                    Some(_) => ']',
+                    None => '.',
+                };
+                (p, c)
+            })
+            .collect()
+    }
+}
+
+// The first cell, top to bottom, where the grids disagree
+fn first_difference(
+    expected: &HashMap<(i32, i32), char>,
+    actual: &HashMap<(i32, i32), char>,
+) -> Option<((i32, i32), char, char)> {
+    let mut cells = expected.keys().collect::<Vec<_>>();
+    cells.sort();
+    cells
+        .into_iter()
+        .find(|p| actual.get(p) != Some(&expected[p]))
+        .map(|p| (*p, expected[p], actual.get(p).copied().unwrap_or(' ')))
+}
+
+// move_grid_2 on the wide warehouse against pushing whole boxes. While every
+// move so far is vertical, the wide grid also has to be the narrow one after
+// move_grid, expanded; a horizontal move shifts wide boxes by half a narrow
+// cell, so the two stop lining up after that.
+fn check_wide_moves(w: &WarehouseMoves) -> Option<Result<(), String>> {
+    let mut narrow = w.grid.clone();
+    let mut narrow_pos = find_robot_pos(&narrow);
+    let mut wide = expand_grid(&w.grid);
+    let mut pos = find_robot_pos(&wide);
+    let mut boxes = WideBoxes::new(&wide);
+    let mut aligned = true;
+    for (i, &direction) in w.moves.iter().enumerate() {
+        let m = Move { direction };
+        pos = move_grid_2(&mut wide, pos, &m);
+        boxes.push(direction);
+        let expected = boxes.render(wide.keys().cloned());
+        if let Some((p, e, a)) = first_difference(&expected, &wide) {
+            return Some(Err(format!(
+                "move {} ({}): {:?} is {:?} after move_grid_2, {:?} pushing whole boxes",
+                i, direction, p, a, e
+            )));
+        }
+        if pos != boxes.robot {
+            return Some(Err(format!(
+                "move {} ({}): move_grid_2 left the robot at {:?}, not {:?}",
+                i, direction, pos, boxes.robot
+            )));
+        }
+        aligned &= direction == '^' || direction == 'v';
+        if aligned {
+            narrow_pos = move_grid(&mut narrow, narrow_pos, &m);
+            if let Some((p, e, a)) = first_difference(&expand_grid(&narrow), &wide) {
+                return Some(Err(format!(
+                    "move {} ({}): {:?} is {:?} after move_grid_2, {:?} in the expanded move_grid",
+                    i, direction, p, a, e
+                )));
+            }
+        }
+    }
+    Some(Ok(()))
+}
+
+fn wide_counts(grid: &HashMap<(i32, i32), char>) -> (usize, Vec<(i32, i32)>) {
+    let boxes = grid
+        .iter()
+        .filter(|(&(i, j), &c)| c == '[' && grid.get(&(i, j + 1)) == Some(&']'))
+        .count();
+    let mut walls = grid
+        .iter()
+        .filter(|(_, &c)| c == '#')
+        .map(|(p, _)| *p)
+        .collect::<Vec<_>>();
+    walls.sort();
+    (boxes, walls)
+}
+
+// Moving never makes or breaks a box, never splits one, and never moves a wall
+fn check_wide_invariants(w: &WarehouseMoves) -> Option<Result<(), String>> {
+    let mut wide = expand_grid(&w.grid);
+    let mut pos = find_robot_pos(&wide);
+    let (boxes, walls) = wide_counts(&wide);
+    for (i, &direction) in w.moves.iter().enumerate() {
+        pos = move_grid_2(&mut wide, pos, &Move { direction });
+        let (boxes_now, walls_now) = wide_counts(&wide);
+        let halves = wide.values().filter(|&&c| c == '[' || c == ']').count();
+        if boxes_now != boxes || halves != 2 * boxes {
+            return Some(Err(format!(
+                "move {} ({}): {} whole boxes and {} halves, started with {} boxes",
+                i, direction, boxes_now, halves, boxes
+            )));
+        }
+        if walls_now != walls {
+            return Some(Err(format!("move {} ({}): the walls moved", i, direction)));
+        }
+    }
+    Some(Ok(()))
+}
+
+pub fn properties() -> Vec<Box<dyn Check>> {
+    vec![
+        Box::new(Property {
+            name: "day15-wide-moves",
+            file: file!(),
+            generate: random_warehouse_moves,
+            shrink: shrink_warehouse_moves,
+            check: check_wide_moves,
+            show: show_warehouse_moves,
+        }),
+        Box::new(Property {
+            name: "day15-wide-invariants",
+            file: file!(),
+            generate: random_warehouse_moves,
+            shrink: shrink_warehouse_moves,
+            check: check_wide_invariants,
+            show: show_warehouse_moves,
+        }),
+    ]
 }
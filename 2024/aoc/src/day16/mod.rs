@@ -1,19 +1,8 @@
-use crate::util::grid;
-use std::{
-    collections::{BTreeMap, HashMap},
-    fs,
-};
-
-const DEFAULT_INPUT_FILE: &str = "input.txt";
+use crate::util::{grid, input};
+use std::collections::{BTreeMap, HashMap};
 
 pub fn main(_args: Vec<String>) {
-    let this_file = file!();
-    let this_dir = std::path::Path::new(this_file).parent().unwrap();
-    let mut file_name = DEFAULT_INPUT_FILE;
-    if _args.len() >= 1 {
-        file_name = &_args[0];
-    }
-    let input = fs::read_to_string(this_dir.join(file_name)).unwrap();
+    let input = input::load(file!(), &_args);
 
     let parsed = grid::parse_input_to_grid(&input);
     let result = part1(&parsed);
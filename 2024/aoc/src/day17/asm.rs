@@ -0,0 +1,94 @@
+// Mnemonic source to a 3-bit program, the other way round from `disasm`. One
+// instruction per line, e.g. `adv 3`, `out A` or `jnz loop`; `name:` labels
+// the next instruction and `;` or `#` starts a comment.
+use super::disasm::MNEMONICS;
+use std::{collections::HashMap, fmt};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AsmError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for AsmError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+fn is_label(s: &str) -> bool {
+    s.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn literal(s: &str) -> Option<usize> {
+    s.parse().ok().filter(|&v| v < 8)
+}
+
+fn combo(s: &str) -> Option<usize> {
+    match s.to_ascii_uppercase().as_str() {
+        "A" => Some(4),
+        "B" => Some(5),
+        "C" => Some(6),
+        _ => literal(s).filter(|&v| v < 4),
+    }
+}
+
+pub fn assemble(source: &str) -> Result<Vec<usize>, AsmError> {
+    // Labels can be used before they're defined, so find them all first
+    let mut labels = HashMap::new();
+    let mut code = vec![];
+    for (i, line) in source.lines().enumerate() {
+        let error = |message| AsmError {
+            line: i + 1,
+            message,
+        };
+        let mut rest = line.split([';', '#']).next().unwrap().trim();
+        while let Some((label, after)) = rest.split_once(':') {
+            let label = label.trim();
+            if !is_label(label) {
+                return Err(error(format!("invalid label {:?}", label)));
+            }
+            if labels.insert(label, 2 * code.len()).is_some() {
+                return Err(error(format!("label {} is defined twice", label)));
+            }
+            rest = after.trim();
+        }
+        if !rest.is_empty() {
+            code.push((i + 1, rest));
+        }
+    }
+
+    let mut program = vec![];
+    for (line, text) in code {
+        let error = |message| AsmError { line, message };
+        let words = text.split_whitespace().collect::<Vec<_>>();
+        let opcode = MNEMONICS
+            .iter()
+            .position(|m| m.eq_ignore_ascii_case(words[0]))
+            .ok_or_else(|| error(format!("unknown instruction {}", words[0])))?;
+        let operand = match (opcode, &words[1..]) {
+            // bxc ignores its operand, so it's optional
+            (4, []) => 0,
+            (_, []) => return Err(error(format!("{} needs an operand", MNEMONICS[opcode]))),
+            (3, &[target]) if labels.contains_key(target) => {
+                let addr = labels[target];
+                if addr >= 8 {
+                    return Err(error(format!(
+                        "label {} is at {}, out of reach of a 3-bit jump",
+                        target, addr
+                    )));
+                }
+                addr
+            }
+            (1 | 3 | 4, &[x]) => literal(x)
+                .ok_or_else(|| error(format!("expected a number from 0 to 7, got {}", x)))?,
+            (_, &[x]) => {
+                combo(x).ok_or_else(|| error(format!("expected 0-3, A, B or C, got {}", x)))?
+            }
+            (_, extra) => return Err(error(format!("unexpected {}", extra[1..].join(" ")))),
+        };
+        program.extend([opcode, operand]);
+    }
+    Ok(program)
+}
@@ -0,0 +1,233 @@
+// The small programs from the puzzle text, plus a few of our own for the
+// corners the text only describes, run as part of `aoc check` and
+// `cargo test`
+use super::{
+    debug::{Debugger, Stop},
+    ProgramState, Registers, VmError,
+};
+use crate::util::prop::{self, Check, Failure, Outcome};
+
+const NAME: &str = "day17-conformance";
+
+#[derive(Debug, Clone, Copy)]
+enum Expect {
+    Output(&'static [i64]),
+    A(i64),
+    B(i64),
+    C(i64),
+    Error(VmError),
+}
+
+struct Case {
+    description: &'static str,
+    registers: (i64, i64, i64),
+    program: &'static [usize],
+    expect: &'static [Expect],
+}
+
+const CASES: &[Case] = &[
+    Case {
+        description: "If register C contains 9, the program 2,6 would set register B to 1",
+        registers: (0, 0, 9),
+        program: &[2, 6],
+        expect: &[Expect::B(1)],
+    },
+    Case {
+        description: "If register A contains 10, the program 5,0,5,1,5,4 would output 0,1,2",
+        registers: (10, 0, 0),
+        program: &[5, 0, 5, 1, 5, 4],
+        expect: &[Expect::Output(&[0, 1, 2])],
+    },
+    Case {
+        description: "If register A contains 2024, the program 0,1,5,4,3,0 would output \
+                      4,2,5,6,7,7,7,7,3,1,0 and leave 0 in register A",
+        registers: (2024, 0, 0),
+        program: &[0, 1, 5, 4, 3, 0],
+        expect: &[
+            Expect::Output(&[4, 2, 5, 6, 7, 7, 7, 7, 3, 1, 0]),
+            Expect::A(0),
+        ],
+    },
+    Case {
+        description: "If register B contains 29, the program 1,7 would set register B to 26",
+        registers: (0, 29, 0),
+        program: &[1, 7],
+        expect: &[Expect::B(26)],
+    },
+    Case {
+        description: "If register B contains 2024 and register C contains 43690, the program \
+                      4,0 would set register B to 44354",
+        registers: (0, 2024, 43690),
+        program: &[4, 0],
+        expect: &[Expect::B(44354)],
+    },
+    Case {
+        description: "Part 1 example",
+        registers: (729, 0, 0),
+        program: &[0, 1, 5, 4, 3, 0],
+        expect: &[Expect::Output(&[4, 6, 3, 5, 6, 3, 5, 2, 1, 0])],
+    },
+    Case {
+        description: "Part 2 example: A = 117440 outputs the program itself",
+        registers: (117440, 0, 0),
+        program: &[0, 3, 5, 4, 3, 0],
+        expect: &[Expect::Output(&[0, 3, 5, 4, 3, 0])],
+    },
+    Case {
+        description: "bdv and cdv keep every bit of the quotient",
+        registers: (1 << 40, 0, 0),
+        program: &[6, 1, 7, 2],
+        expect: &[Expect::B(1 << 39), Expect::C(1 << 38)],
+    },
+    Case {
+        description: "Dividing by 2^B for a huge B gives 0 instead of overflowing",
+        registers: (12345, 1000, 0),
+        program: &[0, 5],
+        expect: &[Expect::A(0)],
+    },
+    Case {
+        description: "Combo operand 7 is reserved",
+        registers: (1, 0, 0),
+        program: &[0, 7],
+        expect: &[Expect::Error(VmError::InvalidOperand {
+            pointer: 0,
+            operand: 7,
+        })],
+    },
+    Case {
+        description: "An opcode above 7 is invalid, whatever its operand",
+        registers: (0, 0, 0),
+        program: &[8, 7],
+        expect: &[Expect::Error(VmError::InvalidOpcode {
+            pointer: 0,
+            opcode: 8,
+        })],
+    },
+    Case {
+        description: "Dividing by 2^B for a negative B is an error",
+        registers: (12345, -1, 0),
+        program: &[0, 5],
+        expect: &[Expect::Error(VmError::NegativeShift {
+            pointer: 0,
+            shift: -1,
+        })],
+    },
+    Case {
+        description: "Division truncates a negative A towards 0",
+        registers: (-7, 0, 0),
+        program: &[0, 1],
+        expect: &[Expect::A(-3)],
+    },
+    Case {
+        description: "bst and out take a negative value modulo 8 into 0..8",
+        registers: (-3, 0, 0),
+        program: &[2, 4, 5, 4],
+        expect: &[Expect::B(5), Expect::Output(&[5])],
+    },
+    Case {
+        description: "bxc ignores its operand, even a 7",
+        registers: (0, 6, 3),
+        program: &[4, 7],
+        expect: &[Expect::B(5)],
+    },
+    Case {
+        description: "Reading an operand past the end halts",
+        registers: (0, 0, 0),
+        program: &[5, 4, 5],
+        expect: &[Expect::Output(&[0])],
+    },
+];
+
+impl Case {
+    fn state(&self) -> ProgramState {
+        let (a, b, c) = self.registers;
+        ProgramState {
+            reg: Registers { a, b, c },
+            program: self.program.to_vec(),
+            pointer: 0,
+        }
+    }
+
+    // In the puzzle's input format, so a failure can be run as day 17
+    fn show(&self) -> String {
+        let (a, b, c) = self.registers;
+        let program = self
+            .program
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        format!(
+            "Register A: {}\nRegister B: {}\nRegister C: {}\n\nProgram: {}",
+            a, b, c, program
+        )
+    }
+
+    fn check(&self) -> Result<(), String> {
+        let mut debugger = Debugger::new(self.state());
+        let stop = debugger.run(None, &mut |_| {});
+        let failed = self.expect.iter().find(|&&expect| match expect {
+            Expect::Error(e) => stop != Stop::Error(e),
+            _ if stop != Stop::Halted => true,
+            Expect::Output(output) => debugger.outputs != output,
+            Expect::A(a) => debugger.state.reg.a != a,
+            Expect::B(b) => debugger.state.reg.b != b,
+            Expect::C(c) => debugger.state.reg.c != c,
+        });
+        match failed {
+            None => Ok(()),
+            Some(expect) => Err(format!(
+                "{}: expected {:?}, got {} with {:?} and output {:?}",
+                self.description, expect, stop, debugger.state.reg, debugger.outputs
+            )),
+        }
+    }
+}
+
+struct Conformance;
+
+impl Check for Conformance {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    // Always the same cases, so the seed and case count don't apply
+    fn run(&self, seed: u64, _cases: usize) -> Result<Outcome, Failure> {
+        for (i, case) in CASES.iter().enumerate() {
+            if let Err(message) = case.check() {
+                let input = case.show();
+                return Err(Failure {
+                    name: NAME,
+                    seed,
+                    case: i,
+                    shrinks: 0,
+                    message,
+                    path: prop::save_failure(file!(), NAME, &input),
+                    input,
+                });
+            }
+        }
+        Ok(Outcome {
+            passed: CASES.len(),
+            skipped: 0,
+        })
+    }
+}
+
+pub fn properties() -> Vec<Box<dyn Check>> {
+    vec![Box::new(Conformance)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_case() {
+        let failures = CASES
+            .iter()
+            .filter_map(|case| case.check().err())
+            .collect::<Vec<_>>();
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+}
@@ -0,0 +1,199 @@
+use super::{disasm::Instruction, step, ProgramState, VmError};
+use std::fmt;
+
+pub const DEFAULT_MAX_STEPS: usize = 100_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Radix {
+    Decimal,
+    Octal,
+}
+
+impl Radix {
+    pub fn format(&self, value: i64) -> String {
+        match self {
+            Radix::Decimal => value.to_string(),
+            Radix::Octal => format!("{:o}", value),
+        }
+    }
+}
+
+// One executed instruction, with the registers on either side of it
+#[derive(Debug, Clone)]
+pub struct TraceEntry {
+    pub step: usize,
+    pub instruction: Instruction,
+    pub before: [i64; 3],
+    pub after: [i64; 3],
+    pub output: Option<i64>,
+}
+
+impl TraceEntry {
+    pub fn format(&self, radix: Radix) -> String {
+        let regs = |r: &[i64; 3]| {
+            format!(
+                "A={} B={} C={}",
+                radix.format(r[0]),
+                radix.format(r[1]),
+                radix.format(r[2])
+            )
+        };
+        let mut line = format!(
+            "{:5}  {:3}: {},{} {:<6}  {} -> {}",
+            self.step,
+            self.instruction.addr,
+            self.instruction.opcode,
+            self.instruction.operand,
+            self.instruction.asm(),
+            regs(&self.before),
+            regs(&self.after)
+        );
+        if let Some(output) = self.output {
+            line.push_str(&format!("  out {}", output));
+        }
+        line
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Breakpoint {
+    // before the instruction at this address runs
+    Pointer(usize),
+    // after an `out`, of any value or of this one
+    Output(Option<i64>),
+}
+
+impl fmt::Display for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Breakpoint::Pointer(p) => write!(f, "pointer {}", p),
+            Breakpoint::Output(None) => write!(f, "any output"),
+            Breakpoint::Output(Some(v)) => write!(f, "output {}", v),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    Halted,
+    Breakpoint(Breakpoint),
+    StepLimit(usize),
+    Error(VmError),
+    // `run` was asked for a fixed number of steps and took them all
+    Paused,
+}
+
+impl fmt::Display for Stop {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Stop::Halted => write!(f, "halted"),
+            Stop::Breakpoint(b) => write!(f, "stopped at breakpoint on {}", b),
+            Stop::StepLimit(n) => write!(f, "gave up after {} steps", n),
+            Stop::Error(e) => write!(f, "error: {}", e),
+            Stop::Paused => write!(f, "paused"),
+        }
+    }
+}
+
+// A program run that can be stopped, inspected and changed part way through
+#[derive(Debug, Clone)]
+pub struct Debugger {
+    pub state: ProgramState,
+    pub outputs: Vec<i64>,
+    pub steps: usize,
+    pub max_steps: usize,
+    pub breakpoints: Vec<Breakpoint>,
+}
+
+impl Debugger {
+    pub fn new(state: ProgramState) -> Self {
+        Self {
+            state,
+            outputs: vec![],
+            steps: 0,
+            max_steps: DEFAULT_MAX_STEPS,
+            breakpoints: vec![],
+        }
+    }
+
+    pub fn registers(&self) -> [i64; 3] {
+        [self.state.reg.a, self.state.reg.b, self.state.reg.c]
+    }
+
+    pub fn set_register(&mut self, name: char, value: i64) -> Result<(), String> {
+        match self.state.reg.get_mut(name) {
+            Some(r) => {
+                *r = value;
+                Ok(())
+            }
+            None => Err(format!("no register {}", name)),
+        }
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.state.pointer + 1 >= self.state.program.len()
+    }
+
+    // Runs one instruction; None if the program has already halted
+    pub fn step(&mut self) -> Result<Option<TraceEntry>, VmError> {
+        if self.is_halted() {
+            return Ok(None);
+        }
+        let instruction = Instruction {
+            addr: self.state.pointer,
+            opcode: self.state.program[self.state.pointer],
+            operand: self.state.program[self.state.pointer + 1],
+        };
+        let before = self.registers();
+        let (output, _) = step(&mut self.state)?;
+        self.steps += 1;
+        if let Some(output) = output {
+            self.outputs.push(output);
+        }
+        Ok(Some(TraceEntry {
+            step: self.steps,
+            instruction,
+            before,
+            after: self.registers(),
+            output,
+        }))
+    }
+
+    // Runs until the program halts, a breakpoint hits, `n` steps have been
+    // taken or the step limit is reached. A pointer breakpoint on the current
+    // instruction doesn't fire straight away, so a stopped run can continue.
+    pub fn run(&mut self, n: Option<usize>, trace: &mut dyn FnMut(&TraceEntry)) -> Stop {
+        let mut taken = 0;
+        loop {
+            if taken > 0 {
+                if let Some(&b) = self
+                    .breakpoints
+                    .iter()
+                    .find(|&&b| b == Breakpoint::Pointer(self.state.pointer))
+                {
+                    return Stop::Breakpoint(b);
+                }
+            }
+            if n == Some(taken) {
+                return Stop::Paused;
+            }
+            if self.steps >= self.max_steps {
+                return Stop::StepLimit(self.steps);
+            }
+            let entry = match self.step() {
+                Ok(Some(entry)) => entry,
+                Ok(None) => return Stop::Halted,
+                Err(e) => return Stop::Error(e),
+            };
+            taken += 1;
+            trace(&entry);
+            if let Some(output) = entry.output {
+                if let Some(&b) = self.breakpoints.iter().find(|&&b| {
+                    b == Breakpoint::Output(None) || b == Breakpoint::Output(Some(output))
+                }) {
+                    return Stop::Breakpoint(b);
+                }
+            }
+        }
+    }
+}
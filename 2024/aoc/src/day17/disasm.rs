@@ -0,0 +1,138 @@
+// Readable views of a 3-bit program: an address listing and pseudo-code
+
+pub const MNEMONICS: [&str; 8] = ["adv", "bxl", "bst", "jnz", "bxc", "out", "bdv", "cdv"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Instruction {
+    pub addr: usize,
+    pub opcode: usize,
+    pub operand: usize,
+}
+
+impl Instruction {
+    pub fn mnemonic(&self) -> &'static str {
+        MNEMONICS.get(self.opcode).unwrap_or(&"???")
+    }
+
+    fn is_literal(&self) -> bool {
+        [1, 3].contains(&self.opcode)
+    }
+
+    // bxc reads its operand but ignores it
+    fn ignores_operand(&self) -> bool {
+        self.opcode == 4
+    }
+
+    // Operand as the instruction sees it: a number, or the register a combo
+    // operand refers to
+    pub fn decoded_operand(&self) -> String {
+        if self.is_literal() {
+            return self.operand.to_string();
+        }
+        combo(self.operand)
+    }
+
+    pub fn asm(&self) -> String {
+        if self.ignores_operand() {
+            self.mnemonic().to_string()
+        } else {
+            format!("{} {}", self.mnemonic(), self.decoded_operand())
+        }
+    }
+
+    // What the instruction does, as a statement
+    pub fn effect(&self) -> String {
+        let x = self.decoded_operand();
+        match self.opcode {
+            0 => format!("A = A >> {}", x),
+            1 => format!("B = B ^ {}", x),
+            2 => format!("B = {} % 8", x),
+            3 => format!("if A != 0 goto {}", x),
+            4 => "B = B ^ C".to_string(),
+            5 => format!("out({} % 8)", x),
+            6 => format!("B = A >> {}", x),
+            7 => format!("C = A >> {}", x),
+            _ => format!("invalid opcode {}", self.opcode),
+        }
+    }
+}
+
+pub fn combo(operand: usize) -> String {
+    match operand {
+        0..=3 => operand.to_string(),
+        4 => "A".to_string(),
+        5 => "B".to_string(),
+        6 => "C".to_string(),
+        _ => format!("<invalid {}>", operand),
+    }
+}
+
+// A trailing opcode without an operand gets operand 0, as if the program
+// were padded
+pub fn decode(program: &[usize]) -> Vec<Instruction> {
+    program
+        .chunks(2)
+        .enumerate()
+        .map(|(i, pair)| Instruction {
+            addr: 2 * i,
+            opcode: pair[0],
+            operand: pair.get(1).cloned().unwrap_or(0),
+        })
+        .collect()
+}
+
+pub fn listing(program: &[usize]) -> String {
+    decode(program)
+        .iter()
+        .map(|ins| {
+            format!(
+                "{:3}:  {},{}  {:<7}  {}",
+                ins.addr,
+                ins.opcode,
+                ins.operand,
+                ins.asm(),
+                ins.effect()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// The usual shape is one loop closed by a jnz at the very end; that becomes a
+// do/while. Anything else gets labels and gotos.
+pub fn pseudo_code(program: &[usize]) -> String {
+    let instructions = decode(program);
+    let jumps = instructions
+        .iter()
+        .filter(|ins| ins.opcode == 3)
+        .collect::<Vec<_>>();
+    let mut lines = vec![];
+    match jumps[..] {
+        [last] if Some(last) == instructions.last() && last.operand < last.addr => {
+            for ins in instructions.iter().filter(|ins| ins.addr < last.operand) {
+                lines.push(ins.effect());
+            }
+            lines.push("do {".to_string());
+            for ins in instructions
+                .iter()
+                .filter(|ins| ins.addr >= last.operand && ins.addr < last.addr)
+            {
+                lines.push(format!("    {}", ins.effect()));
+            }
+            lines.push("} while A != 0".to_string());
+        }
+        _ => {
+            let targets = jumps.iter().map(|ins| ins.operand).collect::<Vec<_>>();
+            for ins in instructions.iter() {
+                if targets.contains(&ins.addr) {
+                    lines.push(format!("L{}:", ins.addr));
+                }
+                match ins.opcode {
+                    3 => lines.push(format!("    if A != 0 goto L{}", ins.operand)),
+                    _ => lines.push(format!("    {}", ins.effect())),
+                }
+            }
+        }
+    }
+    lines.join("\n")
+}
@@ -1,17 +1,22 @@
-use rand::prelude::*;
+use crate::{
+    repl::{self, Session},
+    util::input,
+};
 use regex::Regex;
-use std::{collections::HashMap, fs};
+use std::{fmt, fs, path::Path};
 
-const DEFAULT_INPUT_FILE: &str = "input.txt";
+mod asm;
+mod conformance;
+mod debug;
+mod disasm;
+mod search;
+
+pub use conformance::properties;
+
+use debug::{Breakpoint, Debugger, Radix, Stop};
 
 pub fn main(_args: Vec<String>) {
-    let this_file = file!();
-    let this_dir = std::path::Path::new(this_file).parent().unwrap();
-    let mut file_name = DEFAULT_INPUT_FILE;
-    if _args.len() >= 1 {
-        file_name = &_args[0];
-    }
-    let input = fs::read_to_string(this_dir.join(file_name)).unwrap();
+    let input = input::load(file!(), &_args);
 
     let parsed = parse_input(&input);
     let result = part1(&parsed);
@@ -24,16 +29,17 @@ fn parse_input(input: &str) -> ProgramState {
     let re_register = Regex::new(r"Register (\w): (\d+)").unwrap();
     let re_program = Regex::new(r"Program: ([\d,]+)").unwrap();
     let mut ps = ProgramState {
-        reg: HashMap::new(),
+        reg: Registers::default(),
         program: vec![],
         pointer: 0,
     };
+    let mut source = vec![];
     for line in input.lines() {
         if line.starts_with("Register") {
             let captures = re_register.captures(line).unwrap();
             let name = captures.get(1).unwrap().as_str().chars().next().unwrap();
             let value = captures.get(2).unwrap().as_str().parse::<i64>().unwrap();
-            ps.reg.insert(name, value);
+            *ps.reg.get_mut(name).unwrap() = value;
         } else if line.starts_with("Program") {
             let captures = re_program.captures(line).unwrap();
             let program = captures
@@ -44,210 +50,432 @@ fn parse_input(input: &str) -> ProgramState {
                 .map(|s| s.parse::<usize>().unwrap())
                 .collect::<Vec<usize>>();
             ps.program = program;
+        } else {
+            source.push(line);
         }
     }
+    // Without a "Program:" line, the rest is taken to be mnemonic source
+    if ps.program.is_empty() {
+        ps.program = asm::assemble(&source.join("\n")).unwrap_or_else(|e| panic!("{}", e));
+    }
     ps
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+struct Registers {
+    a: i64,
+    b: i64,
+    c: i64,
+}
+
+impl Registers {
+    fn get_mut(&mut self, name: char) -> Option<&mut i64> {
+        match name {
+            'A' => Some(&mut self.a),
+            'B' => Some(&mut self.b),
+            'C' => Some(&mut self.c),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 struct ProgramState {
-    reg: HashMap<char, i64>,
+    reg: Registers,
     program: Vec<usize>,
     pointer: usize,
 }
 
-impl ProgramState {
-    fn rget(&self, name: char) -> i64 {
-        *self.reg.get(&name).unwrap()
-    }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VmError {
+    InvalidOpcode { pointer: usize, opcode: usize },
+    // combo operand 7 is reserved
+    InvalidOperand { pointer: usize, operand: usize },
+    // a "dv" by a negative register, which would multiply instead
+    NegativeShift { pointer: usize, shift: i64 },
+}
 
-    fn rset(&mut self, name: char, value: i64) {
-        self.reg.insert(name, value);
+impl fmt::Display for VmError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VmError::InvalidOpcode { pointer, opcode } => {
+                write!(f, "invalid opcode {} at {}", opcode, pointer)
+            }
+            VmError::InvalidOperand { pointer, operand } => {
+                write!(f, "invalid combo operand {} at {}", operand, pointer)
+            }
+            VmError::NegativeShift { pointer, shift } => {
+                write!(f, "division by 2^{} at {}", shift, pointer)
+            }
+        }
     }
 }
 
-fn get_operand_value(registers: &HashMap<char, i64>, operator: usize, operand: usize) -> i64 {
+fn get_operand_value(ps: &ProgramState, operator: usize, operand: usize) -> Result<i64, VmError> {
     let is_literal = [1, 3].contains(&operator);
     if is_literal {
-        operand as i64
-    } else {
-        match operand {
-            0 | 1 | 2 | 3 => operand as i64,
-            4 => *registers.get(&'A').unwrap(),
-            5 => *registers.get(&'B').unwrap(),
-            6 => *registers.get(&'C').unwrap(),
-            _ => panic!("Invalid operand: {}", operand),
-        }
+        return Ok(operand as i64);
+    }
+    match operand {
+        0..=3 => Ok(operand as i64),
+        4 => Ok(ps.reg.a),
+        5 => Ok(ps.reg.b),
+        6 => Ok(ps.reg.c),
+        _ => Err(VmError::InvalidOperand {
+            pointer: ps.pointer,
+            operand,
+        }),
+    }
+}
+
+// The "dv" instructions divide by 2^operand, truncating like the spec says,
+// so a negative register (set from the REPL) rounds towards 0. Any i64 over
+// 2^64 is 0.
+fn shr(ps: &ProgramState, value: i64, shift: i64) -> Result<i64, VmError> {
+    if shift < 0 {
+        return Err(VmError::NegativeShift {
+            pointer: ps.pointer,
+            shift,
+        });
     }
+    Ok((value as i128 / (1_i128 << shift.min(64))) as i64)
 }
 
-fn step(ps: &mut ProgramState) -> (Option<i64>, bool) {
+// Output of the instruction at the pointer, if any, and whether the program
+// is still running
+fn step(ps: &mut ProgramState) -> Result<(Option<i64>, bool), VmError> {
     let mut output = None;
-    if ps.pointer >= ps.program.len() {
-        return (output, false);
+    // reading an opcode or its operand past the end halts
+    if ps.pointer + 1 >= ps.program.len() {
+        return Ok((output, false));
     }
     let operator = ps.program[ps.pointer];
-    let operand = get_operand_value(&ps.reg, operator, ps.program[ps.pointer + 1]);
-    // println!(
-    //     "pointer: {}, operator: {}, operand: {}",
-    //     ps.pointer, operator, operand
-    // );
-    // let operator_str = match operator {
-    //     0 => "0        adv",
-    //     1 => " 1       bxl",
-    //     2 => "  2      bst",
-    //     3 => "   3     jnz",
-    //     4 => "    4    bxc",
-    //     5 => "     5   out",
-    //     6 => "      6  bdv",
-    //     7 => "       7 cdv",
-    //     _ => panic!("Invalid operator: {}", operator),
-    // };
-    // println!(
-    //     "{} {} {:o} {:o} {:o}",
-    //     operator_str,
-    //     ps.program[ps.pointer + 1],
-    //     ps.rget('A'),
-    //     ps.rget('B'),
-    //     ps.rget('C')
-    // );
+    if operator > 7 {
+        return Err(VmError::InvalidOpcode {
+            pointer: ps.pointer,
+            opcode: operator,
+        });
+    }
+    // bxc ignores its operand, so don't let an unused 7 fail the program
+    let operand = if operator == 4 {
+        0
+    } else {
+        get_operand_value(ps, operator, ps.program[ps.pointer + 1])?
+    };
     match operator {
         0 => {
             // `adv`
-            ps.rset('A', ps.rget('A') / 2_i64.pow(operand as u32));
+            ps.reg.a = shr(ps, ps.reg.a, operand)?;
         }
         1 => {
             // `bxl`
-            ps.rset('B', ps.rget('B') ^ operand);
+            ps.reg.b ^= operand;
         }
         2 => {
             // `bst`
-            ps.rset('B', operand % 8);
+            ps.reg.b = operand.rem_euclid(8);
         }
         3 => {
             // `jnz`
-            if ps.rget('A') != 0 {
+            if ps.reg.a != 0 {
                 ps.pointer = operand as usize;
-                return (output, true);
+                return Ok((output, true));
             }
         }
         4 => {
             // `bxc`
-            ps.rset('B', (ps.rget('B') ^ ps.rget('C')) % 8);
+            ps.reg.b ^= ps.reg.c;
         }
         5 => {
             // `out`
-            output = Some(operand % 8);
+            output = Some(operand.rem_euclid(8));
         }
         6 => {
             // `bdv`
-            ps.rset('B', ps.rget('A') / 2_i64.pow(operand as u32) % 8);
+            ps.reg.b = shr(ps, ps.reg.a, operand)?;
         }
         7 => {
             // `cdv`
-            ps.rset('C', ps.rget('A') / 2_i64.pow(operand as u32) % 8);
+            ps.reg.c = shr(ps, ps.reg.a, operand)?;
         }
-        _ => panic!("Invalid operator: {}", operator),
+        _ => unreachable!(),
     }
     ps.pointer += 2;
-    (output, true)
+    Ok((output, true))
 }
 
-fn calculate_outputs(input: &ProgramState) -> Vec<usize> {
-    let mut ps = input.clone();
-    let mut outputs: Vec<usize> = vec![];
-    while let (output, true) = step(&mut ps) {
-        if output.is_some() {
-            outputs.push(output.unwrap() as usize);
-        }
+// Fails rather than hanging if the program runs past the step limit
+fn calculate_outputs(input: &ProgramState) -> Result<Vec<usize>, String> {
+    let mut debugger = Debugger::new(input.clone());
+    match debugger.run(None, &mut |_| {}) {
+        Stop::Halted => Ok(debugger.outputs.iter().map(|&o| o as usize).collect()),
+        stop => Err(stop.to_string()),
     }
-    outputs
 }
 
-fn calculate_outputs_for_a(input: &ProgramState, a: i64) -> Vec<usize> {
+fn calculate_outputs_for_a(input: &ProgramState, a: i64) -> Result<Vec<usize>, String> {
     let mut ps = input.clone();
-    ps.rset('A', a);
+    ps.reg.a = a;
     calculate_outputs(&ps)
 }
 
-fn calculate_outputs_str(input: &ProgramState) -> String {
-    calculate_outputs(input)
+fn outputs_str(outputs: &[usize]) -> String {
+    outputs
         .iter()
         .map(|o| o.to_string())
         .collect::<Vec<String>>()
         .join(",")
 }
 
+fn program_line(program: &[usize]) -> String {
+    format!("Program: {}", outputs_str(program))
+}
+
+fn calculate_outputs_str(input: &ProgramState) -> Result<String, String> {
+    calculate_outputs(input).map(|outputs| outputs_str(&outputs))
+}
+
 fn part1(input: &ProgramState) -> String {
-    calculate_outputs_str(input)
+    calculate_outputs_str(input).unwrap_or_else(|e| e)
 }
 
-fn vec_eq(a: &[usize], b: &[usize]) -> bool {
-    a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| a == b)
+fn part2(input: &ProgramState) -> String {
+    match search::find_a(input, &input.program) {
+        Ok(a) => a.to_string(),
+        Err(e) => e,
+    }
 }
 
-fn pick_rand(v: &Vec<usize>) -> usize {
-    let mut rng = rand::thread_rng();
-    let i = rng.gen_range(0..v.len());
-    v[i]
+struct ReplSession {
+    input: ProgramState,
+    debugger: Debugger,
+    radix: Radix,
 }
 
-fn probe(input: &ProgramState) -> Option<i64> {
-    let mut a = 0;
-    input.program.iter().enumerate().rev().for_each(|(i, _)| {
-        // find 'a' such that output is the tail end of input.program
-        a <<= 3;
-        let valid_a_nexts: Vec<_> = (0..=7)
-            .filter(|&a_next| {
-                let mut ps = input.clone();
-                ps.rset('A', a + a_next);
-                let outputs = calculate_outputs(&ps);
-                vec_eq(&outputs, &input.program[i..])
-            })
-            .map(|a_next| a_next as usize)
-            .collect();
-        // println!("len valid_a_nexts: {}", valid_a_nexts.len());
-        if valid_a_nexts.len() > 0 {
-            a += pick_rand(&valid_a_nexts) as i64;
+impl Session for ReplSession {
+    fn part1(&self) -> String {
+        part1(&self.input)
+    }
+
+    fn part2(&self) -> String {
+        part2(&self.input)
+    }
+
+    fn commands(&self) -> Vec<(&'static str, &'static str)> {
+        vec![
+            (
+                "run <a>",
+                "output of the program with register A set to <a>",
+            ),
+            ("disasm", "listing of the program with decoded operands"),
+            ("pseudo", "the program as pseudo-code"),
+            (
+                "shape",
+                "whether A can be searched for a few bits at a time",
+            ),
+            (
+                "find <outputs>",
+                "smallest A that outputs <outputs>, e.g. 1,2,3",
+            ),
+            (
+                "asm <file>",
+                "assemble mnemonic source from <file> and load it",
+            ),
+            (
+                "trace <a>",
+                "every step of a run with register A set to <a>",
+            ),
+            ("radix dec|oct", "show registers in decimal or octal"),
+            (
+                "load [a]",
+                "start debugging, optionally with register A set to <a>",
+            ),
+            ("step [n]", "run one (or n) instructions, tracing them"),
+            (
+                "continue",
+                "run until a breakpoint, the end or the step limit",
+            ),
+            (
+                "break <pointer>",
+                "stop before the instruction at <pointer>",
+            ),
+            (
+                "break out [value]",
+                "stop after any output, or after <value>",
+            ),
+            ("clear", "remove all breakpoints"),
+            ("set <register> <value>", "change A, B or C"),
+            ("regs", "pointer, registers and output so far"),
+            ("limit <n>", "give up after <n> steps (default 100000)"),
+        ]
+    }
+
+    fn run(&mut self, command: &str, args: &[&str]) -> Option<Result<String, String>> {
+        match command {
+            "run" => Some(
+                repl::arg(args, 0, "a")
+                    .and_then(|a| calculate_outputs_for_a(&self.input, a))
+                    .map(|outputs| outputs_str(&outputs)),
+            ),
+            "disasm" => Some(Ok(disasm::listing(&self.input.program))),
+            "pseudo" => Some(Ok(disasm::pseudo_code(&self.input.program))),
+            "shape" => Some(Ok(match search::analyse(&self.input.program) {
+                Ok(shape) => format!(
+                    "each pass shifts A right by {} bits and outputs {} times",
+                    shape.shift, shape.outputs
+                ),
+                Err(reason) => format!("no, because {}", reason),
+            })),
+            "find" => Some(self.find(args)),
+            "asm" => Some(self.assemble(args)),
+            "trace" => Some(self.trace(args)),
+            "radix" => Some(self.set_radix(args)),
+            "load" => Some(self.load(args)),
+            "step" => Some(self.step(args)),
+            "continue" => Some(Ok(self.resume(None))),
+            "break" => Some(self.add_breakpoint(args)),
+            "clear" => {
+                self.debugger.breakpoints.clear();
+                Some(Ok("breakpoints cleared".to_string()))
+            }
+            "set" => Some(self.set_register(args)),
+            "regs" => Some(Ok(self.regs())),
+            "limit" => Some(repl::arg(args, 0, "n").map(|n| {
+                self.debugger.max_steps = n;
+                format!("step limit {}", n)
+            })),
+            _ => None,
         }
-    });
-    let expected = input.program.clone();
-    let actual = calculate_outputs_for_a(&input, a);
-    if vec_eq(&expected, &actual) {
-        Some(a)
-    } else {
-        None
     }
 }
 
-fn part2(input: &ProgramState) -> String {
-    // let expected = input
-    //     .program
-    //     .iter()
-    //     .map(|o| o.to_string())
-    //     .collect::<Vec<String>>()
-    //     .join(",");
-    // println!("{} -- expected", expected);
-
-    let mut iter = 0;
-    let mut found: HashMap<i64, i64> = HashMap::new();
-    loop {
-        iter += 1;
-        if iter % 1000 == 0 {
-            // println!("{} -- {:?}", iter, found);
-        }
-        let a = probe(input);
-        if a.is_some() {
-            let a = a.unwrap();
-            found.insert(a, found.get(&a).unwrap_or(&0) + 1);
+impl ReplSession {
+    fn find(&self, args: &[&str]) -> Result<String, String> {
+        let target = repl::arg::<String>(args, 0, "outputs")?
+            .split(',')
+            .map(|o| o.parse::<usize>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| format!("invalid <outputs>: {}", args[0]))?;
+        search::find_a(&self.input, &target).map(|a| a.to_string())
+    }
+
+    // Replaces the program but keeps the registers, so any "Register" lines
+    // in the file are skipped
+    fn assemble(&mut self, args: &[&str]) -> Result<String, String> {
+        let file = repl::arg::<String>(args, 0, "file")?;
+        let path = Path::new(file!()).parent().unwrap().join(&file);
+        let source = fs::read_to_string(&path).map_err(|e| format!("{}: {}", file, e))?;
+        let source = source
+            .lines()
+            .map(|line| {
+                if line.starts_with("Register") {
+                    ""
+                } else {
+                    line
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.input.program = asm::assemble(&source).map_err(|e| format!("{}: {}", file, e))?;
+        self.debugger = Debugger::new(self.input.clone());
+        Ok(program_line(&self.input.program))
+    }
+
+    fn trace(&self, args: &[&str]) -> Result<String, String> {
+        let mut debugger = Debugger::new(self.input.clone());
+        debugger.max_steps = self.debugger.max_steps;
+        debugger.set_register('A', repl::arg(args, 0, "a")?)?;
+        let mut lines = vec![];
+        let stop = debugger.run(None, &mut |entry| lines.push(entry.format(self.radix)));
+        lines.push(stop.to_string());
+        Ok(lines.join("\n"))
+    }
+
+    fn set_radix(&mut self, args: &[&str]) -> Result<String, String> {
+        self.radix = match args.first() {
+            Some(&"dec") => Radix::Decimal,
+            Some(&"oct") => Radix::Octal,
+            _ => return Err("expected dec or oct".to_string()),
+        };
+        Ok(format!("radix {}", args[0]))
+    }
+
+    fn load(&mut self, args: &[&str]) -> Result<String, String> {
+        let mut debugger = Debugger::new(self.input.clone());
+        debugger.max_steps = self.debugger.max_steps;
+        debugger.breakpoints = self.debugger.breakpoints.clone();
+        if !args.is_empty() {
+            debugger.set_register('A', repl::arg(args, 0, "a")?)?;
         }
-        if found.len() > 0 && found.values().min().unwrap() > &1 {
-            break;
+        self.debugger = debugger;
+        Ok(self.regs())
+    }
+
+    fn step(&mut self, args: &[&str]) -> Result<String, String> {
+        let n = if args.is_empty() {
+            1
+        } else {
+            repl::arg(args, 0, "n")?
+        };
+        Ok(self.resume(Some(n)))
+    }
+
+    // Runs the debugger on, tracing only when a step count is given
+    fn resume(&mut self, n: Option<usize>) -> String {
+        let radix = self.radix;
+        let mut lines = vec![];
+        let stop = self.debugger.run(n, &mut |entry| {
+            if n.is_some() {
+                lines.push(entry.format(radix));
+            }
+        });
+        if stop != Stop::Paused {
+            lines.push(stop.to_string());
         }
+        lines.push(self.regs());
+        lines.join("\n")
+    }
+
+    fn add_breakpoint(&mut self, args: &[&str]) -> Result<String, String> {
+        let breakpoint = match args.first() {
+            Some(&"out") if args.len() == 1 => Breakpoint::Output(None),
+            Some(&"out") => Breakpoint::Output(Some(repl::arg(args, 1, "value")?)),
+            _ => Breakpoint::Pointer(repl::arg(args, 0, "pointer")?),
+        };
+        self.debugger.breakpoints.push(breakpoint);
+        Ok(format!("breakpoint on {}", breakpoint))
+    }
+
+    fn set_register(&mut self, args: &[&str]) -> Result<String, String> {
+        let name = repl::arg::<char>(args, 0, "register")?.to_ascii_uppercase();
+        self.debugger
+            .set_register(name, repl::arg(args, 1, "value")?)?;
+        Ok(self.regs())
+    }
+
+    fn regs(&self) -> String {
+        let [a, b, c] = self.debugger.registers().map(|r| self.radix.format(r));
+        let outputs = self
+            .debugger
+            .outputs
+            .iter()
+            .map(|o| o.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        format!(
+            "pointer {}, A={} B={} C={}, {} steps, output [{}]",
+            self.debugger.state.pointer, a, b, c, self.debugger.steps, outputs
+        )
     }
-    let min_a = *found.keys().min().unwrap();
+}
 
-    // println!("{} -- {:o}", calculate_outputs_str(&input), min_a);
+pub fn session(_args: Vec<String>) -> Box<dyn Session> {
+    let input = input::load(file!(), &_args);
+    let input = parse_input(&input);
 
-    min_a.to_string()
+    Box::new(ReplSession {
+        debugger: Debugger::new(input.clone()),
+        input,
+        radix: Radix::Decimal,
+    })
 }
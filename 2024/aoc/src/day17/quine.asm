@@ -0,0 +1,7 @@
+; The part 2 example: outputs itself when A = 117440
+Register A: 2024
+
+loop:
+    adv 3       ; A = A >> 3
+    out A
+    jnz loop
\ No newline at end of file
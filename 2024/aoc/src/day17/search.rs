@@ -0,0 +1,196 @@
+// Finding a value of A that makes a program print a given output. Programs
+// shaped like the puzzle's can be solved a few bits at a time; anything else
+// gets a bounded brute force.
+use super::{
+    calculate_outputs_for_a,
+    debug::{Breakpoint, Debugger, Stop},
+    disasm::{combo, decode, Instruction},
+    outputs_str, ProgramState,
+};
+
+pub const BRUTE_FORCE_LIMIT: i64 = 1 << 16;
+
+// A single loop over the whole program that shifts A right by `shift` bits
+// and prints `outputs` values per pass, each depending only on A
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shape {
+    pub shift: i64,
+    pub outputs: usize,
+}
+
+// Registers an instruction reads, and the one it writes
+fn registers(ins: &Instruction) -> (Vec<char>, Option<char>) {
+    let combo = match ins.operand {
+        4 => Some('A'),
+        5 => Some('B'),
+        6 => Some('C'),
+        _ => None,
+    };
+    let mut reads = match ins.opcode {
+        0 | 3 | 6 | 7 => vec!['A'],
+        1 => vec!['B'],
+        4 => vec!['B', 'C'],
+        _ => vec![],
+    };
+    if ![1, 3, 4].contains(&ins.opcode) {
+        reads.extend(combo);
+    }
+    let writes = match ins.opcode {
+        0 => Some('A'),
+        1 | 2 | 4 | 6 => Some('B'),
+        7 => Some('C'),
+        _ => None,
+    };
+    (reads, writes)
+}
+
+// Why the program can't be searched digit by digit, if it can't
+pub fn analyse(program: &[usize]) -> Result<Shape, String> {
+    if !program.len().is_multiple_of(2) {
+        return Err("the program has an odd length".to_string());
+    }
+    let instructions = decode(program);
+    if let Some(ins) = instructions
+        .iter()
+        .find(|ins| ins.opcode > 7 || ins.operand > 7)
+    {
+        return Err(format!(
+            "{},{} at {} is not 3-bit",
+            ins.opcode, ins.operand, ins.addr
+        ));
+    }
+    match instructions
+        .iter()
+        .filter(|ins| ins.opcode == 3)
+        .collect::<Vec<_>>()[..]
+    {
+        [jnz] if Some(jnz) == instructions.last() && jnz.operand == 0 => {}
+        _ => return Err("it isn't one loop closed by `jnz 0` at the end".to_string()),
+    }
+    let mut shift = None;
+    let mut outputs = 0;
+    let mut written = vec![];
+    for ins in instructions.iter() {
+        let (reads, writes) = registers(ins);
+        if ![1, 3, 4].contains(&ins.opcode) && ins.operand == 7 {
+            return Err(format!(
+                "{} at {} uses combo operand 7",
+                ins.mnemonic(),
+                ins.addr
+            ));
+        }
+        if let Some(&r) = reads.iter().find(|&&r| r != 'A' && !written.contains(&r)) {
+            return Err(format!(
+                "{} at {} reads {} left over from the previous pass",
+                ins.mnemonic(),
+                ins.addr,
+                r
+            ));
+        }
+        match (ins.opcode, shift) {
+            (0, None) if (1..=3).contains(&ins.operand) => shift = Some(ins.operand as i64),
+            (0, None) => {
+                return Err(format!(
+                    "adv at {} shifts by {}, not by a constant from 1 to 3",
+                    ins.addr,
+                    combo(ins.operand)
+                ))
+            }
+            (0, Some(_)) => return Err("A is shifted more than once per pass".to_string()),
+            (5, _) => outputs += 1,
+            _ => {}
+        }
+        written.extend(writes);
+    }
+    let Some(shift) = shift else {
+        return Err("there's no adv to shift A".to_string());
+    };
+    if outputs == 0 {
+        return Err("nothing is output".to_string());
+    }
+    Ok(Shape { shift, outputs })
+}
+
+// Each pass prints from the low bits of A (and anything above them) and then
+// drops `shift` of them, so A can be built from the top, one pass at a time,
+// matching the output from the end backwards. Trying digits in ascending
+// order makes the first complete match the smallest.
+fn digit_search(
+    input: &ProgramState,
+    shape: Shape,
+    target: &[usize],
+    a: i64,
+    i: usize,
+) -> Option<i64> {
+    (0..1 << shape.shift).find_map(|digit| {
+        let a = (a << shape.shift) | digit;
+        match calculate_outputs_for_a(input, a) {
+            Ok(outputs) if outputs == target[i..] => {}
+            _ => return None,
+        }
+        if i == 0 {
+            Some(a)
+        } else {
+            digit_search(input, shape, target, a, i - shape.outputs)
+        }
+    })
+}
+
+// Stops as soon as the output goes wrong. A program that doesn't fit the
+// shape may never halt, so each run only gets a few steps per instruction and
+// expected output.
+fn outputs_target(input: &ProgramState, target: &[usize], a: i64) -> bool {
+    let mut debugger = Debugger::new(input.clone());
+    debugger.state.reg.a = a;
+    debugger.max_steps = 4 * input.program.len() * (target.len() + 1);
+    debugger.breakpoints.push(Breakpoint::Output(None));
+    loop {
+        let stop = debugger.run(None, &mut |_| {});
+        let outputs = debugger
+            .outputs
+            .iter()
+            .map(|&o| o as usize)
+            .collect::<Vec<_>>();
+        match stop {
+            Stop::Breakpoint(_) if target.starts_with(&outputs) => {}
+            Stop::Halted => return outputs == target,
+            _ => return false,
+        }
+    }
+}
+
+fn brute_force(input: &ProgramState, target: &[usize], limit: i64) -> Option<i64> {
+    (0..limit).find(|&a| outputs_target(input, target, a))
+}
+
+// Smallest A for which the program outputs `target`
+pub fn find_a(input: &ProgramState, target: &[usize]) -> Result<i64, String> {
+    match analyse(&input.program) {
+        Ok(shape) => {
+            if target.is_empty() || !target.len().is_multiple_of(shape.outputs) {
+                return Err(format!(
+                    "each pass outputs {} values, so nothing outputs {} of them",
+                    shape.outputs,
+                    target.len()
+                ));
+            }
+            let passes = target.len() / shape.outputs;
+            if passes as i64 * shape.shift > 62 {
+                return Err(format!(
+                    "{} passes of {} bits don't fit in register A",
+                    passes, shape.shift
+                ));
+            }
+            digit_search(input, shape, target, 0, target.len() - shape.outputs)
+                .ok_or_else(|| format!("no value of A outputs {}", outputs_str(target)))
+        }
+        Err(reason) => brute_force(input, target, BRUTE_FORCE_LIMIT).ok_or_else(|| {
+            format!(
+                "can't search digit by digit because {}, and no A below {} outputs {}",
+                reason,
+                BRUTE_FORCE_LIMIT,
+                outputs_str(target)
+            )
+        }),
+    }
+}
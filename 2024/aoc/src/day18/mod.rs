@@ -1,15 +1,15 @@
-use std::{collections::HashMap, fs};
-
-const DEFAULT_INPUT_FILE: &str = "input.txt";
+use crate::{
+    gen,
+    util::{
+        dsu, input,
+        prop::{self, Check, Property},
+    },
+};
+use rand::{rngs::StdRng, Rng};
+use std::collections::HashMap;
 
 pub fn main(_args: Vec<String>) {
-    let this_file = file!();
-    let this_dir = std::path::Path::new(this_file).parent().unwrap();
-    let mut file_name = DEFAULT_INPUT_FILE;
-    if _args.len() >= 1 {
-        file_name = &_args[0];
-    }
-    let input = fs::read_to_string(this_dir.join(file_name)).unwrap();
+    let input = input::load(file!(), &_args);
 
     let parsed = parse_input(&input);
     let result = part1(&parsed);
@@ -36,7 +36,7 @@ struct Point {
     y: i32,
 }
 
-fn make_grid(input: &Vec<Point>) -> HashMap<(i32, i32), char> {
+fn make_grid(input: &[Point]) -> HashMap<(i32, i32), char> {
     let mut grid: HashMap<(i32, i32), char> = HashMap::new();
     let min_x = input.iter().map(|p| p.x).min().unwrap();
     let min_y = input.iter().map(|p| p.y).min().unwrap();
@@ -93,25 +93,96 @@ fn calculate_path(grid: &HashMap<(i32, i32), char>) -> Option<i32> {
     None
 }
 
-#[allow(dead_code, unused_variables)]
-fn part1(input: &Vec<Point>) -> i32 {
+// the example drops 12 bytes, a real input a kilobyte
+fn num_ticks(input: &[Point]) -> usize {
+    if input.len() > 1024 {
+        1024
+    } else {
+        12
+    }
+}
+
+// shortest path once the first `n` bytes have fallen
+fn path_after(input: &[Point], n: usize) -> Option<i32> {
     let mut grid = make_grid(input);
-    let num_ticks = if input.len() > 1024 { 1024 } else { 12 };
-    for i in 0..num_ticks {
-        tick(&mut grid, &input[i]);
+    for point in input[..n].iter() {
+        tick(&mut grid, point);
     }
-    calculate_path(&grid).unwrap()
+    calculate_path(&grid)
+}
+
+#[allow(dead_code, unused_variables)]
+fn part1(input: &Vec<Point>) -> i32 {
+    path_after(input, num_ticks(input)).unwrap()
+}
+
+// index of the first byte that cuts the start off from the exit
+fn first_blocking(input: &[Point]) -> Option<usize> {
+    let grid = make_grid(input);
+    let max_x = grid.keys().map(|p| p.0).max().unwrap();
+    let max_y = grid.keys().map(|p| p.1).max().unwrap();
+    let blocks = input.iter().map(|p| (p.x, p.y)).collect::<Vec<_>>();
+    dsu::first_disconnect(grid.keys().cloned(), &blocks, (0, 0), (max_x, max_y))
 }
 
 #[allow(dead_code, unused_variables)]
 fn part2(input: &Vec<Point>) -> String {
-    let mut grid = make_grid(input);
-    let num_ticks = input.len();
-    for i in 0..num_ticks {
-        tick(&mut grid, &input[i]);
-        if calculate_path(&grid) == None {
-            return format!("{},{}", input[i].x, input[i].y);
-        }
+    let i = first_blocking(input).unwrap();
+    format!("{},{}", input[i].x, input[i].y)
+}
+
+// `gen 18 --seed S --size N`, small enough to hit the edge cases
+#[derive(Debug, Clone)]
+struct Generated {
+    seed: u64,
+    size: usize,
+}
+
+fn random_generated(rng: &mut StdRng) -> Generated {
+    Generated {
+        seed: rng.gen(),
+        size: rng.gen_range(0..12),
     }
-    unreachable!()
+}
+
+fn shrink_generated(g: &Generated) -> Vec<Generated> {
+    prop::shrink_int(g.size as i64, 0)
+        .into_iter()
+        .map(|size| Generated {
+            size: size as usize,
+            ..*g
+        })
+        .collect()
+}
+
+fn show_generated(g: &Generated) -> String {
+    gen::generate(18, g.seed, Some(g.size))
+}
+
+// Whatever the generator makes has a path after part 1's bytes and a byte
+// that cuts it off later
+fn check_generated(g: &Generated) -> Option<Result<(), String>> {
+    let input = parse_input(&show_generated(g));
+    let n = num_ticks(&input);
+    let error = if input.len() < n {
+        format!("{} bytes, but part 1 drops {}", input.len(), n)
+    } else if path_after(&input, n).is_none() {
+        format!("the first {} bytes block every path", n)
+    } else if first_blocking(&input).is_none() {
+        "no byte ever blocks the path".to_string()
+    } else {
+        return Some(Ok(()));
+    };
+    Some(Err(format!("size {} (seed {}): {}", g.size, g.seed, error)))
+}
+
+pub fn properties() -> Vec<Box<dyn Check>> {
+    vec![Box::new(Property {
+        name: "day18-generated",
+        file: file!(),
+        generate: random_generated,
+        shrink: shrink_generated,
+        check: check_generated,
+        show: show_generated,
+    })]
 }
@@ -1,18 +1,8 @@
-use std::{
-    collections::{HashMap, HashSet},
-    fs,
-};
-
-const DEFAULT_INPUT_FILE: &str = "input.txt";
+use crate::util::{input, memo::Memo};
+use std::collections::{HashMap, HashSet};
 
 pub fn main(_args: Vec<String>) {
-    let this_file = file!();
-    let this_dir = std::path::Path::new(this_file).parent().unwrap();
-    let mut file_name = DEFAULT_INPUT_FILE;
-    if _args.len() >= 1 {
-        file_name = &_args[0];
-    }
-    let input = fs::read_to_string(this_dir.join(file_name)).unwrap();
+    let input = input::load(file!(), &_args);
 
     let parsed = parse_input(&input);
     let result = part1(&parsed);
@@ -141,21 +131,6 @@ impl Map {
         node_ids.contains(&Self::start_id())
     }
 
-    fn check_sequence_with_count(&self, seq: &str) -> i64 {
-        let mut node_ids: HashMap<i32, i64> = HashMap::from([(Self::start_id(), 1)]);
-        for c in seq.chars() {
-            let mut next_node_ids = HashMap::new();
-            node_ids.iter().for_each(|(&node_id, count)| {
-                let edge_set = self.get_edge(node_id, c);
-                edge_set.iter().for_each(|&edge_node_id| {
-                    *next_node_ids.entry(edge_node_id).or_insert(0) += *count;
-                });
-            });
-            node_ids = next_node_ids;
-        }
-        *node_ids.get(&Self::start_id()).unwrap_or(&0)
-    }
-
     fn build_map(towels: &Vec<String>) -> Map {
         let mut map = Map::new();
         for towel in towels {
@@ -171,11 +146,24 @@ fn part1((towels, sequences): &(Vec<String>, Vec<String>)) -> i32 {
     sequences.iter().filter(|s| map.check_sequence(s)).count() as i32
 }
 
+fn count_arrangements(memo: &mut Memo<String, i64>, towels: &[String], seq: &str) -> i64 {
+    if seq.is_empty() {
+        return 1;
+    }
+    memo.get(seq.to_string(), |memo, seq| {
+        towels
+            .iter()
+            .filter(|towel| seq.starts_with(towel.as_str()))
+            .map(|towel| count_arrangements(memo, towels, &seq[towel.len()..]))
+            .sum()
+    })
+}
+
 #[allow(unused_variables)]
 fn part2((towels, sequences): &(Vec<String>, Vec<String>)) -> i64 {
-    let map = Map::build_map(towels);
+    let mut memo = Memo::new();
     sequences
         .iter()
-        .map(|s| map.check_sequence_with_count(s))
+        .map(|s| count_arrangements(&mut memo, towels, s))
         .sum()
 }
@@ -1,19 +1,8 @@
-use std::{
-    cmp::max,
-    collections::{HashMap, HashSet},
-    fs,
-};
-
-const DEFAULT_INPUT_FILE: &str = "input.txt";
+use crate::util::{input, memo::Memo};
+use std::collections::{HashMap, HashSet};
 
 pub fn main(_args: Vec<String>) {
-    let this_file = file!();
-    let this_dir = std::path::Path::new(this_file).parent().unwrap();
-    let mut file_name = DEFAULT_INPUT_FILE;
-    if _args.len() >= 1 {
-        file_name = &_args[0];
-    }
-    let input = fs::read_to_string(this_dir.join(file_name)).unwrap();
+    let input = input::load(file!(), &_args);
 
     let parsed = parse_input(&input);
     let result = part1(&parsed);
@@ -62,50 +51,59 @@ fn get_directional_keypad_map() -> HashMap<char, (i32, i32)> {
     ])
 }
 
-fn sequence_to_chunk_map(sequence: &Vec<char>) -> HashMap<String, usize> {
-    let mut chunk_map = HashMap::new();
-    let mut ptr = 0;
-    for (i, &c) in sequence.iter().enumerate() {
-        if c == 'A' {
-            let vec_char_str = sequence[ptr..i + 1].iter().collect::<String>();
-            let count = chunk_map.entry(vec_char_str).or_insert(0);
-            *count += 1;
-            ptr = i + 1;
-        }
+// presses on the outermost keypad needed for the robot `depth` keypads away
+// to move from `c1` to `c2` and press it
+fn calculate_press_cost(
+    memo: &mut Memo<(char, char, usize), i64>,
+    key_map: &HashMap<char, (i32, i32)>,
+    c1: char,
+    c2: char,
+    depth: usize,
+) -> i64 {
+    if depth == 0 {
+        return 1;
     }
-    chunk_map
+    memo.get((c1, c2, depth), |memo, _| {
+        get_paths(key_map, c1, c2)
+            .iter()
+            .map(|path| calculate_sequence_cost(memo, key_map, path, depth - 1))
+            .min()
+            .unwrap()
+    })
 }
 
-fn transform_chunk_map(
-    chunk_map: &HashMap<String, usize>,
-    directional_keypad_map: &HashMap<char, (i32, i32)>,
-) -> HashMap<String, usize> {
-    let mut new_chunk_map = HashMap::new();
-    for (chunk, count) in chunk_map {
-        let transformed_chunk =
-            transform_sequence(&chunk.chars().collect(), directional_keypad_map);
-        let transformed_chunk_map = sequence_to_chunk_map(&transformed_chunk);
-        for (transformed_chunk, transformed_count) in transformed_chunk_map {
-            let new_count = new_chunk_map.entry(transformed_chunk).or_insert(0);
-            *new_count += transformed_count * count;
-        }
+fn calculate_sequence_cost(
+    memo: &mut Memo<(char, char, usize), i64>,
+    key_map: &HashMap<char, (i32, i32)>,
+    sequence: &str,
+    depth: usize,
+) -> i64 {
+    let mut c1 = 'A';
+    let mut cost = 0;
+    for c2 in sequence.chars() {
+        cost += calculate_press_cost(memo, key_map, c1, c2, depth);
+        c1 = c2;
     }
-    new_chunk_map
+    cost
 }
 
 fn calculate_min_length(sequence: &Vec<char>, n_intermediates: usize) -> i64 {
     let numeric_keypad_map = get_numeric_keypad_map();
     let directional_keypad_map = get_directional_keypad_map();
-    let sequence = transform_sequence(&sequence, &numeric_keypad_map);
-    let mut chunk_map = sequence_to_chunk_map(&sequence);
-    for _ in 0..n_intermediates {
-        chunk_map = transform_chunk_map(&chunk_map, &directional_keypad_map);
+    let mut memo = Memo::new();
+    let mut c1 = 'A';
+    let mut length = 0;
+    for &c2 in sequence {
+        length += get_paths(&numeric_keypad_map, c1, c2)
+            .iter()
+            .map(|path| {
+                calculate_sequence_cost(&mut memo, &directional_keypad_map, path, n_intermediates)
+            })
+            .min()
+            .unwrap();
+        c1 = c2;
     }
-    chunk_map
-        .iter()
-        .map(|(k, v)| k.len() * v)
-        .map(|x| x as i64)
-        .sum::<i64>()
+    length
 }
 
 fn is_valid_sequence(sequence: &str, key_map: &HashMap<char, (i32, i32)>, from: char) -> bool {
@@ -134,26 +132,23 @@ fn is_valid_sequence(sequence: &str, key_map: &HashMap<char, (i32, i32)>, from:
     true
 }
 
-fn transform_sequence(sequence: &Vec<char>, key_map: &HashMap<char, (i32, i32)>) -> Vec<char> {
-    let mut movements: Vec<char> = vec![];
-    for (i, _) in sequence.iter().enumerate() {
-        let c1 = if i == 0 { 'A' } else { sequence[i - 1] };
-        let c2 = sequence[i];
-        let dx = key_map[&c2].1 - key_map[&c1].1;
-        let dy = key_map[&c2].0 - key_map[&c1].0;
-        let dir_l = "<".repeat(max(-dx, 0) as usize);
-        let dir_d = "v".repeat(max(dy, 0) as usize);
-        let dir_u = "^".repeat(max(-dy, 0) as usize);
-        let dir_r = ">".repeat(max(dx, 0) as usize);
-        let mut result = format!("{}{}{}{}", dir_l, dir_d, dir_u, dir_r);
-
-        if !is_valid_sequence(&result, key_map, c1) {
-            result = result.chars().rev().collect::<String>();
-        }
-        movements.extend(result.chars());
-        movements.push('A');
-    }
-    movements
+// the (at most two) straight-line paths from `c1` to `c2` that stay on the
+// keypad, each followed by the 'A' press
+fn get_paths(key_map: &HashMap<char, (i32, i32)>, c1: char, c2: char) -> Vec<String> {
+    let dx = key_map[&c2].1 - key_map[&c1].1;
+    let dy = key_map[&c2].0 - key_map[&c1].0;
+    let horizontal = if dx < 0 { "<" } else { ">" }.repeat(dx.unsigned_abs() as usize);
+    let vertical = if dy < 0 { "^" } else { "v" }.repeat(dy.unsigned_abs() as usize);
+    let mut paths = vec![
+        format!("{}{}", horizontal, vertical),
+        format!("{}{}", vertical, horizontal),
+    ];
+    paths.dedup();
+    paths
+        .into_iter()
+        .filter(|path| is_valid_sequence(path, key_map, c1))
+        .map(|path| path + "A")
+        .collect()
 }
 
 fn calculate_numeric_part(sequence: &Vec<char>) -> usize {
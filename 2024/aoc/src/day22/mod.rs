@@ -1,15 +1,11 @@
-use std::{collections::HashMap, fs};
-
-const DEFAULT_INPUT_FILE: &str = "input.txt";
+use crate::{
+    repl::{self, Session},
+    util::{input, simulation::Simulation},
+};
+use std::collections::HashMap;
 
 pub fn main(_args: Vec<String>) {
-    let this_file = file!();
-    let this_dir = std::path::Path::new(this_file).parent().unwrap();
-    let mut file_name = DEFAULT_INPUT_FILE;
-    if _args.len() >= 1 {
-        file_name = &_args[0];
-    }
-    let input = fs::read_to_string(this_dir.join(file_name)).unwrap();
+    let input = input::load(file!(), &_args);
 
     let parsed = parse_input(&input);
     let result = part1(&parsed);
@@ -56,13 +52,16 @@ impl Secret {
         self.prune();
     }
 
-    fn handle_deltas(&mut self, values_map: &mut HashMap<(i64, i64, i64, i64), i64>) {
+    fn handle_deltas(&mut self) {
         self.deltas = [
             self.deltas[1],
             self.deltas[2],
             self.deltas[3],
             self.val % 10 - self.prev % 10,
         ];
+    }
+
+    fn record_price(&self, values_map: &mut HashMap<(i64, i64, i64, i64), i64>) {
         let key = (
             self.deltas[0],
             self.deltas[1],
@@ -79,13 +78,24 @@ impl Secret {
         self.div(32);
         self.mult(2048);
     }
+}
+
+impl Simulation for Secret {
+    type Snapshot = Secret;
 
-    fn next_2(&mut self, values_map: &mut HashMap<(i64, i64, i64, i64), i64>) {
+    fn step(&mut self) -> bool {
         self.prev = self.val;
-        self.mult(64);
-        self.div(32);
-        self.mult(2048);
-        self.handle_deltas(values_map);
+        self.next();
+        self.handle_deltas();
+        true
+    }
+
+    fn snapshot(&self) -> Self::Snapshot {
+        self.clone()
+    }
+
+    fn restore(&mut self, snapshot: Self::Snapshot) {
+        *self = snapshot;
     }
 }
 
@@ -95,28 +105,102 @@ fn part1(input: &Vec<Secret>) -> i64 {
         .iter()
         .map(|secret| secret.clone())
         .map(|mut secret| {
-            for _ in 0..2000 {
-                secret.next();
-            }
+            secret.run(2000);
             secret.val
         })
         .sum()
 }
 
-#[allow(unused)]
-fn part2(input: &Vec<Secret>) -> i64 {
+// Bananas from every buyer, keyed by the four price changes that sell
+fn total_prices(input: &[Secret]) -> HashMap<(i64, i64, i64, i64), i64> {
     let mut values_map: HashMap<(i64, i64, i64, i64), i64> = HashMap::new();
     input
         .iter()
         .map(|secret| secret.clone())
         .for_each(|mut secret| {
             let mut new_values_map = HashMap::new();
-            for _ in 0..2000 {
-                secret.next_2(&mut new_values_map);
-            }
+            secret.run_observed(2000, &mut |_, secret| {
+                secret.record_price(&mut new_values_map);
+            });
             new_values_map.iter().for_each(|(key, value)| {
                 *values_map.entry(*key).or_insert(0) += *value;
             });
         });
-    *values_map.values().max().unwrap()
+    values_map
+}
+
+#[allow(unused)]
+fn part2(input: &Vec<Secret>) -> i64 {
+    *total_prices(input).values().max().unwrap()
+}
+
+struct ReplSession {
+    input: Vec<Secret>,
+    // filled in by the first `price`
+    prices: Option<HashMap<(i64, i64, i64, i64), i64>>,
+}
+
+impl Session for ReplSession {
+    fn part1(&self) -> String {
+        part1(&self.input).to_string()
+    }
+
+    fn part2(&self) -> String {
+        part2(&self.input).to_string()
+    }
+
+    fn commands(&self) -> Vec<(&'static str, &'static str)> {
+        vec![
+            (
+                "price <d1> <d2> <d3> <d4>",
+                "bananas from selling on these price changes",
+            ),
+            (
+                "secret <buyer> <n>",
+                "the buyer's secret number after n steps",
+            ),
+        ]
+    }
+
+    fn run(&mut self, command: &str, args: &[&str]) -> Option<Result<String, String>> {
+        match command {
+            "price" => Some(self.price(args)),
+            "secret" => Some(self.secret(args)),
+            _ => None,
+        }
+    }
+}
+
+impl ReplSession {
+    fn price(&mut self, args: &[&str]) -> Result<String, String> {
+        let key = (
+            repl::arg(args, 0, "d1")?,
+            repl::arg(args, 1, "d2")?,
+            repl::arg(args, 2, "d3")?,
+            repl::arg(args, 3, "d4")?,
+        );
+        let prices = self.prices.get_or_insert_with(|| total_prices(&self.input));
+        Ok(prices.get(&key).unwrap_or(&0).to_string())
+    }
+
+    fn secret(&self, args: &[&str]) -> Result<String, String> {
+        let buyer: usize = repl::arg(args, 0, "buyer")?;
+        let n = repl::arg(args, 1, "n")?;
+        let mut secret = self
+            .input
+            .get(buyer)
+            .ok_or(format!("there are only {} buyers", self.input.len()))?
+            .clone();
+        secret.run(n);
+        Ok(secret.val.to_string())
+    }
+}
+
+pub fn session(_args: Vec<String>) -> Box<dyn Session> {
+    let input = input::load(file!(), &_args);
+
+    Box::new(ReplSession {
+        input: parse_input(&input),
+        prices: None,
+    })
 }
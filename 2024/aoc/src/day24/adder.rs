@@ -0,0 +1,155 @@
+// Finding the swapped outputs in a circuit that's meant to be a ripple-carry
+// adder. Bit i adds x_i and y_i with a half adder (XOR and AND), the XOR of
+// that with the carry in gives z_i, and the two ANDs are ORed into the carry
+// out. The last carry is the top z bit.
+use super::{
+    arith,
+    circuit::{Circuit, CircuitError},
+    Gate, Wire,
+};
+use itertools::Itertools;
+use rand::{rngs::StdRng, SeedableRng};
+use std::collections::{HashMap, HashSet};
+
+const ADDITIONS: usize = 100;
+// 10395 ways to pair up 12 wires; the puzzle has four swaps
+const MAX_MISPLACED: usize = 12;
+
+fn is_input(wire: &str) -> bool {
+    wire.starts_with('x') || wire.starts_with('y')
+}
+
+fn bit(wire: &str) -> Option<usize> {
+    wire[1..].parse().ok()
+}
+
+pub fn input_bits(wires: &HashMap<String, Wire>) -> usize {
+    wires.keys().filter(|k| k.starts_with('x')).count()
+}
+
+// Outputs of gates that don't fit the adder's structure, sorted
+pub fn misplaced_outputs(wires: &HashMap<String, Wire>, gates: &HashSet<Gate>) -> Vec<String> {
+    let top = format!("z{:02}", input_bits(wires));
+    let mut consumers: HashMap<&str, Vec<&str>> = HashMap::new();
+    for gate in gates.iter() {
+        consumers.entry(&gate.wire1).or_default().push(&gate.op);
+        consumers.entry(&gate.wire2).or_default().push(&gate.op);
+    }
+    let feeds = |gate: &Gate, op: &str| {
+        consumers
+            .get(gate.dest.as_str())
+            .is_some_and(|ops| ops.contains(&op))
+    };
+    gates
+        .iter()
+        .filter(|gate| {
+            let from_inputs = is_input(&gate.wire1) && is_input(&gate.wire2);
+            let first_bit = from_inputs && bit(&gate.wire1) == Some(0);
+            let to_z = gate.dest.starts_with('z');
+            match gate.op.as_str() {
+                _ if gate.dest == top => gate.op != "OR",
+                "XOR" if first_bit => gate.dest != "z00",
+                // half adder sum, XORed with the carry in
+                "XOR" if from_inputs => to_z || !feeds(gate, "XOR"),
+                "XOR" => !to_z,
+                // the first carry goes straight into bit 1
+                "AND" if first_bit => to_z || !feeds(gate, "XOR"),
+                "AND" => to_z || !feeds(gate, "OR"),
+                "OR" => to_z || !feeds(gate, "XOR"),
+                _ => true,
+            }
+        })
+        .map(|gate| gate.dest.clone())
+        .sorted()
+        .collect()
+}
+
+// The gates with the outputs of each pair exchanged
+pub fn swap_outputs(gates: &HashSet<Gate>, swaps: &[(String, String)]) -> HashSet<Gate> {
+    gates
+        .iter()
+        .map(|gate| {
+            let mut gate = gate.clone();
+            for (a, b) in swaps.iter() {
+                if gate.dest == *a {
+                    gate.dest = b.clone();
+                } else if gate.dest == *b {
+                    gate.dest = a.clone();
+                }
+            }
+            gate
+        })
+        .collect()
+}
+
+// Every bit's full adder on its own, then random sums and one that carries
+// all the way up
+pub fn adds_correctly(wires: &HashMap<String, Wire>, gates: &HashSet<Gate>) -> bool {
+    let Ok(circuit) = Circuit::compile(wires, gates) else {
+        return false;
+    };
+    if arith::first_failing_bit(&circuit).is_some() {
+        return false;
+    }
+    let bits = circuit.input_bits();
+    let mut pairs = vec![(arith::mask(bits), 1)];
+    pairs.extend(arith::random_pairs(
+        &mut StdRng::seed_from_u64(0),
+        bits,
+        ADDITIONS,
+    ));
+    arith::add_all(&circuit, &pairs)
+        .iter()
+        .all(|addition| addition.is_right())
+}
+
+// Every way of splitting the wires into pairs
+fn pairings(wires: &[String]) -> Vec<Vec<(String, String)>> {
+    let Some((first, rest)) = wires.split_first() else {
+        return vec![vec![]];
+    };
+    let mut result = vec![];
+    for i in 0..rest.len() {
+        let mut others = rest.to_vec();
+        let partner = others.remove(i);
+        for mut pairing in pairings(&others) {
+            pairing.push((first.clone(), partner.clone()));
+            result.push(pairing);
+        }
+    }
+    result
+}
+
+// The misplaced outputs, paired up so that the circuit adds
+pub fn find_swaps(
+    wires: &HashMap<String, Wire>,
+    gates: &HashSet<Gate>,
+) -> Result<Vec<(String, String)>, String> {
+    // no swap changes which input bits there are
+    if let Err(e @ CircuitError::TooWide(_)) = Circuit::compile(wires, gates) {
+        return Err(e.to_string());
+    }
+    let misplaced = misplaced_outputs(wires, gates);
+    if !misplaced.len().is_multiple_of(2) {
+        return Err(format!(
+            "{} misplaced outputs ({}) can't be paired up",
+            misplaced.len(),
+            misplaced.join(",")
+        ));
+    }
+    if misplaced.len() > MAX_MISPLACED {
+        return Err(format!(
+            "{} misplaced outputs are too many to try every pairing; is it an adder?",
+            misplaced.len()
+        ));
+    }
+    pairings(&misplaced)
+        .into_iter()
+        .find(|swaps| adds_correctly(wires, &swap_outputs(gates, swaps)))
+        .ok_or_else(|| {
+            format!(
+                "no pairing of {} makes the circuit add",
+                misplaced.join(",")
+            )
+        })
+}
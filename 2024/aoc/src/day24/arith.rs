@@ -0,0 +1,84 @@
+// Checking a circuit's additions against x + y, for chosen inputs or bit by
+// bit
+use super::circuit::{Circuit, LANES};
+use rand::Rng;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Addition {
+    pub x: u64,
+    pub y: u64,
+    pub z: u64,
+}
+
+impl Addition {
+    pub fn expected(&self) -> u64 {
+        self.x + self.y
+    }
+
+    // z bits that differ from x + y, lowest first
+    pub fn wrong_bits(&self) -> Vec<usize> {
+        let wrong = self.z ^ self.expected();
+        (0..u64::BITS as usize)
+            .filter(|bit| wrong >> bit & 1 == 1)
+            .collect()
+    }
+
+    pub fn is_right(&self) -> bool {
+        self.z == self.expected()
+    }
+}
+
+// Runs the pairs through the circuit, a batch of lanes at a time
+pub fn add_all(circuit: &Circuit, pairs: &[(u64, u64)]) -> Vec<Addition> {
+    pairs
+        .chunks(LANES)
+        .flat_map(|chunk| {
+            circuit
+                .add_batch(chunk)
+                .into_iter()
+                .zip(chunk)
+                .map(|(z, &(x, y))| Addition { x, y, z })
+        })
+        .collect()
+}
+
+// The largest number that fits in `bits` bits
+pub fn mask(bits: usize) -> u64 {
+    1u64.checked_shl(bits as u32).map_or(u64::MAX, |m| m - 1)
+}
+
+pub fn random_pairs(rng: &mut impl Rng, bits: usize, count: usize) -> Vec<(u64, u64)> {
+    (0..count)
+        .map(|_| (rng.gen_range(0..=mask(bits)), rng.gen_range(0..=mask(bits))))
+        .collect()
+}
+
+// Every combination of x_i, y_i and a carry into bit i, the carry made by
+// setting both bits below
+fn bit_cases(bit: usize) -> Vec<(u64, u64)> {
+    let carries = if bit == 0 { 1 } else { 2 };
+    (0..4 * carries)
+        .map(|case| {
+            let carry = (case >> 2) << bit >> 1;
+            (
+                ((case & 1) << bit) | carry,
+                ((case >> 1 & 1) << bit) | carry,
+            )
+        })
+        .collect()
+}
+
+// The lowest input bit where adding x_i and y_i, with or without a carry in,
+// gives the wrong z, with the first addition that shows it. A bad carry out
+// of bit i shows up here as bit i.
+pub fn first_failing_bit(circuit: &Circuit) -> Option<(usize, Addition)> {
+    let cases = (0..circuit.input_bits())
+        .flat_map(|bit| bit_cases(bit).into_iter().map(move |pair| (bit, pair)))
+        .collect::<Vec<_>>();
+    let pairs = cases.iter().map(|&(_, pair)| pair).collect::<Vec<_>>();
+    cases
+        .iter()
+        .zip(add_all(circuit, &pairs))
+        .find(|(_, addition)| !addition.is_right())
+        .map(|(&(bit, _), addition)| (bit, addition))
+}
@@ -0,0 +1,262 @@
+// The netlist compiled for evaluation: wires interned to indices and gates
+// put in topological order, so a run is one pass over a Vec. Every wire holds
+// a u64, which is 64 separate evaluations side by side, one per bit.
+use super::{Gate, Wire};
+use itertools::Itertools;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt,
+};
+
+pub const LANES: usize = 64;
+// so x + y, and every z bit, fits in a u64
+pub const MAX_INPUT_BITS: usize = 63;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    And,
+    Or,
+    Xor,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct CompiledGate {
+    a: usize,
+    b: usize,
+    op: Op,
+    dest: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CircuitError {
+    // read by a gate but neither an input nor driven by one
+    Dangling(Vec<String>),
+    MultipleDrivers(Vec<String>),
+    // the wires around one cycle, each feeding the next
+    Cycle(Vec<String>),
+    // x or y bits from MAX_INPUT_BITS up, or z bits past it
+    TooWide(Vec<String>),
+}
+
+impl fmt::Display for CircuitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CircuitError::Dangling(wires) => write!(f, "undriven wires: {}", wires.join(", ")),
+            CircuitError::MultipleDrivers(wires) => {
+                write!(f, "wires driven more than once: {}", wires.join(", "))
+            }
+            CircuitError::Cycle(wires) => {
+                write!(f, "cycle: {} -> {}", wires.join(" -> "), wires[0])
+            }
+            CircuitError::TooWide(wires) => write!(
+                f,
+                "wires past {} input bits don't fit in a u64: {}",
+                MAX_INPUT_BITS,
+                wires.join(", ")
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Circuit {
+    names: Vec<String>,
+    ids: HashMap<String, usize>,
+    gates: Vec<CompiledGate>,
+    // (bit, wire) of each x, y and z wire
+    x: Vec<(usize, usize)>,
+    y: Vec<(usize, usize)>,
+    z: Vec<(usize, usize)>,
+}
+
+fn bit_wires(ids: &HashMap<String, usize>, prefix: char) -> Vec<(usize, usize)> {
+    ids.iter()
+        .filter(|(name, _)| name.starts_with(prefix))
+        .filter_map(|(name, &id)| Some((name[1..].parse().ok()?, id)))
+        .sorted()
+        .collect()
+}
+
+impl Circuit {
+    pub fn compile(
+        wires: &HashMap<String, Wire>,
+        gates: &HashSet<Gate>,
+    ) -> Result<Circuit, CircuitError> {
+        let mut ids: HashMap<String, usize> = HashMap::new();
+        let mut names = vec![];
+        let mut intern = |name: &str| {
+            *ids.entry(name.to_string()).or_insert_with(|| {
+                names.push(name.to_string());
+                names.len() - 1
+            })
+        };
+        // sorted, so the same netlist always gets the same ids
+        for name in wires.keys().sorted() {
+            intern(name);
+        }
+        let gates = gates
+            .iter()
+            .sorted_by_key(|gate| &gate.dest)
+            .map(|gate| CompiledGate {
+                a: intern(&gate.wire1),
+                b: intern(&gate.wire2),
+                op: match gate.op.as_str() {
+                    "AND" => Op::And,
+                    "OR" => Op::Or,
+                    _ => Op::Xor,
+                },
+                dest: intern(&gate.dest),
+            })
+            .collect::<Vec<_>>();
+
+        let mut driver = vec![None; names.len()];
+        let mut twice = vec![];
+        for (i, gate) in gates.iter().enumerate() {
+            if driver[gate.dest].replace(i).is_some() {
+                twice.push(names[gate.dest].clone());
+            }
+        }
+        if !twice.is_empty() {
+            return Err(CircuitError::MultipleDrivers(twice));
+        }
+        let dangling = gates
+            .iter()
+            .flat_map(|gate| [gate.a, gate.b])
+            .filter(|&w| driver[w].is_none() && !wires.contains_key(&names[w]))
+            .map(|w| names[w].clone())
+            .unique()
+            .sorted()
+            .collect::<Vec<_>>();
+        if !dangling.is_empty() {
+            return Err(CircuitError::Dangling(dangling));
+        }
+
+        // Kahn's algorithm: a gate is ready once the gates driving its inputs are
+        let mut consumers = vec![vec![]; names.len()];
+        let mut waiting = vec![0; gates.len()];
+        for (i, gate) in gates.iter().enumerate() {
+            for w in [gate.a, gate.b] {
+                if driver[w].is_some() {
+                    consumers[w].push(i);
+                    waiting[i] += 1;
+                }
+            }
+        }
+        let mut ready = (0..gates.len())
+            .filter(|&i| waiting[i] == 0)
+            .collect::<VecDeque<_>>();
+        let mut order = vec![];
+        while let Some(i) = ready.pop_front() {
+            order.push(gates[i]);
+            for &j in consumers[gates[i].dest].iter() {
+                waiting[j] -= 1;
+                if waiting[j] == 0 {
+                    ready.push_back(j);
+                }
+            }
+        }
+        if order.len() < gates.len() {
+            // Every gate left waits on another one that's left, so walking
+            // back through them has to come round to a gate seen before
+            let mut seen = vec![];
+            let mut i = (0..gates.len()).find(|&i| waiting[i] > 0).unwrap();
+            while !seen.contains(&i) {
+                seen.push(i);
+                let gate = gates[i];
+                i = [gate.a, gate.b]
+                    .iter()
+                    .filter_map(|&w| driver[w])
+                    .find(|&j| waiting[j] > 0)
+                    .unwrap();
+            }
+            let start = seen.iter().position(|&j| j == i).unwrap();
+            let cycle = seen[start..]
+                .iter()
+                .rev()
+                .map(|&j| names[gates[j].dest].clone())
+                .collect();
+            return Err(CircuitError::Cycle(cycle));
+        }
+
+        let (x, y, z) = (
+            bit_wires(&ids, 'x'),
+            bit_wires(&ids, 'y'),
+            bit_wires(&ids, 'z'),
+        );
+        let too_wide = x
+            .iter()
+            .chain(y.iter())
+            .filter(|&&(bit, _)| bit >= MAX_INPUT_BITS)
+            .chain(z.iter().filter(|&&(bit, _)| bit > MAX_INPUT_BITS))
+            .map(|&(_, w)| names[w].clone())
+            .sorted()
+            .collect::<Vec<_>>();
+        if !too_wide.is_empty() {
+            return Err(CircuitError::TooWide(too_wide));
+        }
+
+        Ok(Circuit {
+            x,
+            y,
+            z,
+            names,
+            ids,
+            gates: order,
+        })
+    }
+
+    pub fn input_bits(&self) -> usize {
+        self.x.len()
+    }
+
+    fn propagate(&self, values: &mut [u64]) {
+        for gate in self.gates.iter() {
+            let (a, b) = (values[gate.a], values[gate.b]);
+            values[gate.dest] = match gate.op {
+                Op::And => a & b,
+                Op::Or => a | b,
+                Op::Xor => a ^ b,
+            };
+        }
+    }
+
+    fn z_value(&self, values: &[u64], lane: usize) -> u64 {
+        self.z
+            .iter()
+            .fold(0, |z, &(bit, w)| z | (values[w] >> lane & 1) << bit)
+    }
+
+    // z for up to 64 (x, y) pairs at once, bit `lane` of every wire holding
+    // pair `lane`
+    pub fn add_batch(&self, pairs: &[(u64, u64)]) -> Vec<u64> {
+        assert!(pairs.len() <= LANES, "at most {} pairs per batch", LANES);
+        let mut values = vec![0u64; self.names.len()];
+        for (lane, &(x, y)) in pairs.iter().enumerate() {
+            for &(bit, w) in self.x.iter() {
+                values[w] |= (x >> bit & 1) << lane;
+            }
+            for &(bit, w) in self.y.iter() {
+                values[w] |= (y >> bit & 1) << lane;
+            }
+        }
+        self.propagate(&mut values);
+        (0..pairs.len())
+            .map(|lane| self.z_value(&values, lane))
+            .collect()
+    }
+
+    // z with every wire the input file gives a value, not just x and y
+    pub fn evaluate(&self, initial: &HashMap<String, Wire>) -> u64 {
+        let mut values = vec![0u64; self.names.len()];
+        for (name, wire) in initial.iter() {
+            values[self.ids[name]] = wire.value as u64 & 1;
+        }
+        self.propagate(&mut values);
+        self.z_value(&values, 0)
+    }
+
+    #[allow(unused)]
+    pub fn add(&self, x: u64, y: u64) -> u64 {
+        self.add_batch(&[(x, y)])[0]
+    }
+}
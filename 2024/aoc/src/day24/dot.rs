@@ -0,0 +1,77 @@
+// The circuit as a Graphviz digraph, with a node per wire and per gate. Render
+// it with e.g. `dot -Tsvg graph.dot -o graph.svg`.
+use super::{Gate, Wire};
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+
+// Keyed by the output, which only one gate drives; two gates with the same
+// inputs and op would share a node otherwise
+fn gate_node(gate: &Gate) -> String {
+    format!("{}_{}", gate.op, gate.dest)
+}
+
+fn gate_style(op: &str) -> &'static str {
+    match op {
+        "AND" => "shape=box, style=filled, fillcolor=lightblue",
+        "OR" => "shape=ellipse, style=filled, fillcolor=palegreen",
+        _ => "shape=diamond, style=filled, fillcolor=lightpink",
+    }
+}
+
+const FLAGGED: &str = "color=red, fontcolor=red, penwidth=3";
+
+// `flagged` wires, and the edges from the gates driving them, are drawn in red
+pub fn to_dot(
+    wires: &HashMap<String, Wire>,
+    gates: &HashSet<Gate>,
+    flagged: &HashSet<String>,
+) -> String {
+    let mut lines = vec!["digraph G {".to_string()];
+
+    // x_i and y_i side by side, in bit order along the top
+    let bits = wires
+        .keys()
+        .filter_map(|name| name[1..].parse::<usize>().ok())
+        .unique()
+        .sorted();
+    for bit in bits {
+        let inputs = ["x", "y"]
+            .iter()
+            .map(|prefix| format!("{}{:02}", prefix, bit))
+            .filter(|name| wires.contains_key(name))
+            .join("; ");
+        lines.push(format!(
+            "  subgraph cluster_bit{:02} {{ label=\"bit {}\"; {}; }}",
+            bit, bit, inputs
+        ));
+    }
+    let outputs = gates
+        .iter()
+        .map(|gate| &gate.dest)
+        .filter(|dest| dest.starts_with('z'))
+        .sorted()
+        .join("; ");
+    lines.push(format!("  {{ rank=sink; {}; }}", outputs));
+
+    for name in flagged.iter().sorted() {
+        lines.push(format!("  {} [{}];", name, FLAGGED));
+    }
+    for gate in gates.iter().sorted_by_key(|gate| &gate.dest) {
+        let node = gate_node(gate);
+        lines.push(format!(
+            "  {} [label=\"{}\", {}];",
+            node,
+            gate.op,
+            gate_style(&gate.op)
+        ));
+        lines.push(format!("  {} -> {};", gate.wire1, node));
+        lines.push(format!("  {} -> {};", gate.wire2, node));
+        if flagged.contains(&gate.dest) {
+            lines.push(format!("  {} -> {} [{}];", node, gate.dest, FLAGGED));
+        } else {
+            lines.push(format!("  {} -> {};", node, gate.dest));
+        }
+    }
+    lines.push("}".to_string());
+    lines.join("\n")
+}
@@ -1,21 +1,26 @@
 use std::{
     collections::{HashMap, HashSet},
     fs,
+    path::Path,
 };
 
+use crate::{
+    repl::{self, Session},
+    util::input,
+};
 use itertools::Itertools;
 use regex::Regex;
 
-const DEFAULT_INPUT_FILE: &str = "input.txt";
+use arith::Addition;
+use circuit::{Circuit, CircuitError};
+
+mod adder;
+mod arith;
+mod circuit;
+mod dot;
 
 pub fn main(_args: Vec<String>) {
-    let this_file = file!();
-    let this_dir = std::path::Path::new(this_file).parent().unwrap();
-    let mut file_name = DEFAULT_INPUT_FILE;
-    if _args.len() >= 1 {
-        file_name = &_args[0];
-    }
-    let input = fs::read_to_string(this_dir.join(file_name)).unwrap();
+    let input = input::load(file!(), &_args);
 
     let parsed = parse_input(&input);
     let result = part1(&parsed);
@@ -69,84 +74,187 @@ fn parse_input(input: &str) -> (HashMap<String, Wire>, HashSet<Gate>) {
     (wires, gates)
 }
 
-fn calculate_gate(gate: &Gate, wires: &mut HashMap<String, Wire>) {
-    let a = wires.get(&gate.wire1).unwrap();
-    let b = wires.get(&gate.wire2).unwrap();
-    let c_val = match gate.op.as_str() {
-        "AND" => a.value & b.value,
-        "OR" => a.value | b.value,
-        "XOR" => a.value ^ b.value,
-        _ => unreachable!(),
-    };
-    wires.insert(
-        gate.dest.clone(),
-        Wire {
-            name: gate.dest.clone(),
-            value: c_val,
-        },
-    );
+#[allow(unused)]
+fn part1(input: &(HashMap<String, Wire>, HashSet<Gate>)) -> String {
+    let (wires, gates) = input;
+    match Circuit::compile(wires, gates) {
+        Ok(circuit) => circuit.evaluate(wires).to_string(),
+        Err(e) => e.to_string(),
+    }
+}
+
+#[allow(unused)]
+fn part2(input: &(HashMap<String, Wire>, HashSet<Gate>)) -> String {
+    match adder::find_swaps(&input.0, &input.1) {
+        Ok(swaps) => swaps.iter().flat_map(|(a, b)| [a, b]).sorted().join(","),
+        Err(e) => e,
+    }
 }
 
-fn from_binary(wires: Vec<usize>) -> usize {
-    wires.iter().fold(0, |acc, &val| acc * 2 + val)
+struct ReplSession {
+    input: (HashMap<String, Wire>, HashSet<Gate>),
 }
 
-fn get_z_wires(wires: &HashMap<String, Wire>, gates: &HashSet<Gate>) -> Vec<usize> {
-    let mut wires = wires.clone();
-    let mut num_gates = gates.len();
-    while num_gates > 0 {
-        let gate = gates
-            .iter()
-            .find(|gate| {
-                wires.contains_key(&gate.wire1)
-                    && wires.contains_key(&gate.wire2)
-                    && !wires.contains_key(&gate.dest)
-            })
-            .unwrap();
-        calculate_gate(gate, &mut wires);
-        num_gates -= 1;
+impl Session for ReplSession {
+    fn part1(&self) -> String {
+        part1(&self.input)
+    }
+
+    fn part2(&self) -> String {
+        part2(&self.input)
+    }
+
+    fn commands(&self) -> Vec<(&'static str, &'static str)> {
+        vec![
+            ("eval <x> <y>", "z output of the circuit for these x and y"),
+            (
+                "dot <file> [x y]",
+                "write the circuit as Graphviz, flagging misplaced outputs or wrong z bits for x + y",
+            ),
+            (
+                "check [x y]",
+                "compare z with x + y (random if not given) and test every bit's adder",
+            ),
+            (
+                "swap <a> <b>",
+                "exchange the outputs of the gates driving <a> and <b>",
+            ),
+            (
+                "swaps",
+                "outputs that don't fit a ripple-carry adder, and how they pair up",
+            ),
+        ]
+    }
+
+    fn run(&mut self, command: &str, args: &[&str]) -> Option<Result<String, String>> {
+        match command {
+            "eval" => Some(self.eval(args)),
+            "check" => Some(self.check(args)),
+            "swap" => Some(self.swap(args)),
+            "swaps" => Some(self.swaps()),
+            "dot" => Some(self.dot(args)),
+            _ => None,
+        }
     }
-    wires
-        .keys()
-        .filter(|key| key.starts_with("z"))
-        .sorted_by_key(|&k| k)
-        .rev()
-        .map(|key| wires.get(key).unwrap().value)
-        .collect_vec()
 }
 
-#[allow(unused)]
-fn part1(input: &(HashMap<String, Wire>, HashSet<Gate>)) -> usize {
-    let wires = &input.0;
-    let gates = &input.1;
-    let z_wires = get_z_wires(wires, gates);
-    from_binary(z_wires)
+impl ReplSession {
+    fn circuit(&self) -> Result<Circuit, String> {
+        Circuit::compile(&self.input.0, &self.input.1).map_err(|e| e.to_string())
+    }
+
+    fn add(&self, x: u64, y: u64) -> Result<Addition, String> {
+        let circuit = self.circuit()?;
+        let bits = circuit.input_bits();
+        if x > arith::mask(bits) || y > arith::mask(bits) {
+            return Err(format!("x and y must fit in {} bits", bits));
+        }
+        Ok(arith::add_all(&circuit, &[(x, y)])[0])
+    }
+
+    fn eval(&self, args: &[&str]) -> Result<String, String> {
+        let x: u64 = repl::arg(args, 0, "x")?;
+        let y: u64 = repl::arg(args, 1, "y")?;
+        let addition = self.add(x, y)?;
+        Ok(format!(
+            "z = {} (x + y = {})",
+            addition.z,
+            addition.expected()
+        ))
+    }
+
+    // x + y for the given or random x and y, then every bit on its own
+    fn check(&self, args: &[&str]) -> Result<String, String> {
+        let circuit = self.circuit()?;
+        let (x, y) = if args.is_empty() {
+            arith::random_pairs(&mut rand::thread_rng(), circuit.input_bits(), 1)[0]
+        } else {
+            (repl::arg(args, 0, "x")?, repl::arg(args, 1, "y")?)
+        };
+        let addition = self.add(x, y)?;
+        let mut lines = vec![format!(
+            "{} + {} = {}, circuit gives {}",
+            x,
+            y,
+            addition.expected(),
+            addition.z
+        )];
+        lines.push(match addition.wrong_bits()[..] {
+            [] => "all z bits right".to_string(),
+            ref bits => format!("wrong z bits: {}", bits.iter().join(", ")),
+        });
+        lines.push(match arith::first_failing_bit(&circuit) {
+            None => "every bit adds and carries correctly".to_string(),
+            Some((bit, a)) => format!(
+                "first failing bit: {} ({} + {} gives wrong z bits {})",
+                bit,
+                a.x,
+                a.y,
+                a.wrong_bits().iter().join(", ")
+            ),
+        });
+        Ok(lines.join("\n"))
+    }
+
+    // Swapping the same pair again undoes it
+    fn swap(&mut self, args: &[&str]) -> Result<String, String> {
+        let a: String = repl::arg(args, 0, "a")?;
+        let b: String = repl::arg(args, 1, "b")?;
+        for wire in [&a, &b] {
+            if !self.input.1.iter().any(|gate| gate.dest == *wire) {
+                return Err(format!("no gate drives {}", wire));
+            }
+        }
+        self.input.1 = adder::swap_outputs(&self.input.1, &[(a.clone(), b.clone())]);
+        Ok(format!("swapped the outputs {} and {}", a, b))
+    }
+
+    fn dot(&self, args: &[&str]) -> Result<String, String> {
+        let file: String = repl::arg(args, 0, "file")?;
+        let (wires, gates) = &self.input;
+        // gate nodes are named after their output, so it has to be unique;
+        // dangling wires and cycles still draw fine
+        if let Err(e @ CircuitError::MultipleDrivers(_)) = Circuit::compile(wires, gates) {
+            return Err(e.to_string());
+        }
+        let flagged: HashSet<String> = if args.len() > 1 {
+            let x: u64 = repl::arg(args, 1, "x")?;
+            let y: u64 = repl::arg(args, 2, "y")?;
+            self.add(x, y)?
+                .wrong_bits()
+                .iter()
+                .map(|bit| format!("z{:02}", bit))
+                .collect()
+        } else {
+            adder::misplaced_outputs(wires, gates).into_iter().collect()
+        };
+        let path = Path::new(file!()).parent().unwrap().join(&file);
+        fs::write(&path, dot::to_dot(wires, gates, &flagged))
+            .map_err(|e| format!("{}: {}", file, e))?;
+        Ok(format!(
+            "wrote {} with {} flagged wires",
+            path.display(),
+            flagged.len()
+        ))
+    }
+
+    fn swaps(&self) -> Result<String, String> {
+        let (wires, gates) = &self.input;
+        let misplaced = adder::misplaced_outputs(wires, gates);
+        let swaps = adder::find_swaps(wires, gates)?;
+        if misplaced.is_empty() {
+            return Ok("nothing is misplaced".to_string());
+        }
+        let mut lines = vec![format!("misplaced: {}", misplaced.join(","))];
+        lines.extend(swaps.iter().map(|(a, b)| format!("swap {} and {}", a, b)));
+        Ok(lines.join("\n"))
+    }
 }
 
-#[allow(unused)]
-fn part2(input: &(HashMap<String, Wire>, HashSet<Gate>)) -> String {
-    // swaps (found by printing out the graphviz visualization):
-    //   1a. y07 AND x07 -/> z07
-    //   1b. pmc XOR mvw -/> gmt
-    //   2a. x11 AND y11 -/> cbj
-    //   2b. y11 XOR x11 -/> qjj
-    //   3a. hch XOR nff -/> dmn
-    //   3b. khk OR  stg -/> z18
-    //   4a. qnm AND rfk -/> z35
-    //   4b. qnm XOR rfk -/> cfk
-    let swaps = vec![
-        "y07 AND x07 -/> z07 -> gmt",
-        "pmc XOR mvw -/> gmt -> z07",
-        "x11 AND y11 -/> cbj -> qjj",
-        "y11 XOR x11 -/> qjj -> cbj",
-        "hch XOR nff -/> dmn -> z18",
-        "khk OR  stg -/> z18 -> dmn",
-        "qnm AND rfk -/> z35 -> cfk",
-        "qnm XOR rfk -/> cfk -> z35",
-    ];
-    swaps
-        .iter()
-        .map(|swap| swap[23..].to_string())
-        .sorted()
-        .join(",")
+pub fn session(_args: Vec<String>) -> Box<dyn Session> {
+    let input = input::load(file!(), &_args);
+
+    Box::new(ReplSession {
+        input: parse_input(&input),
+    })
 }
@@ -1,17 +1,9 @@
-use std::{collections::HashSet, fs};
+use std::collections::HashSet;
 
-use itertools::Itertools;
-
-const DEFAULT_INPUT_FILE: &str = "input.txt";
+use crate::util::{input, parse};
 
 pub fn main(_args: Vec<String>) {
-    let this_file = file!();
-    let this_dir = std::path::Path::new(this_file).parent().unwrap();
-    let mut file_name = DEFAULT_INPUT_FILE;
-    if _args.len() >= 1 {
-        file_name = &_args[0];
-    }
-    let input = fs::read_to_string(this_dir.join(file_name)).unwrap();
+    let input = input::load(file!(), &_args);
 
     let parsed = parse_input(&input);
     let result = part1(&parsed);
@@ -41,10 +33,10 @@ fn parse_input(input: &str) -> (HashSet<Item>, HashSet<Item>) {
     let mut locks: HashSet<Item> = HashSet::new();
     let mut keys: HashSet<Item> = HashSet::new();
 
-    input.lines().enumerate().step_by(8).for_each(|(i, line)| {
-        let item_lines = input.lines().skip(i).take(6).collect_vec();
+    parse::blocks(input).iter().for_each(|block| {
+        let item_lines = block.lines.iter().take(6).cloned().collect();
         let item: Item = parse_item(item_lines);
-        if line.starts_with("#") {
+        if block.lines[0].starts_with("#") {
             locks.insert(item);
         } else {
             keys.insert(item);
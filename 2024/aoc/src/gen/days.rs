@@ -0,0 +1,655 @@
+use super::maze;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+use std::collections::{HashMap, HashSet};
+
+fn lines(lines: impl IntoIterator<Item = String>) -> String {
+    lines.into_iter().collect::<Vec<_>>().join("\n")
+}
+
+fn char_grid(rng: &mut StdRng, n: usize, chars: &[char]) -> Vec<Vec<char>> {
+    (0..n)
+        .map(|_| (0..n).map(|_| *chars.choose(rng).unwrap()).collect())
+        .collect()
+}
+
+fn grid_to_string(grid: &[Vec<char>]) -> String {
+    lines(grid.iter().map(|row| row.iter().collect()))
+}
+
+// size: number of location id pairs
+pub fn day01(rng: &mut StdRng, size: usize) -> String {
+    let lefts = (0..size)
+        .map(|_| rng.gen_range(10000..100000))
+        .collect::<Vec<u32>>();
+    lines((0..size).map(|_| {
+        let left = rng.gen_range(10000..100000);
+        // some right ids repeat a left one so part 2 has something to count
+        let right = if rng.gen_bool(0.3) {
+            *lefts.choose(rng).unwrap()
+        } else {
+            rng.gen_range(10000..100000)
+        };
+        format!("{}   {}", left, right)
+    }))
+}
+
+// size: number of reports
+pub fn day02(rng: &mut StdRng, size: usize) -> String {
+    lines((0..size).map(|_| {
+        let len = rng.gen_range(5..=8);
+        let sign = if rng.gen_bool(0.5) { 1 } else { -1 };
+        let mut level: i32 = rng.gen_range(10..90);
+        let mut levels = vec![level];
+        for _ in 1..len {
+            level += if rng.gen_bool(0.9) {
+                sign * rng.gen_range(1..=3)
+            } else {
+                rng.gen_range(-5..=5)
+            };
+            levels.push(level.max(1));
+        }
+        levels
+            .iter()
+            .map(|l| l.to_string())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }))
+}
+
+// size: number of instructions, valid or corrupted
+pub fn day03(rng: &mut StdRng, size: usize) -> String {
+    let junk = [
+        '!', '@', '#', '$', '%', '^', '&', '*', '(', ')', '[', ']', '<', '>', ' ',
+    ];
+    let words = [
+        "what()", "who()", "select()", "from()", "when()", "how()", "where()",
+    ];
+    let mut memory = String::new();
+    for i in 0..size {
+        for _ in 0..rng.gen_range(0..4) {
+            memory.push(*junk.choose(rng).unwrap());
+        }
+        let (a, b) = (rng.gen_range(1..1000), rng.gen_range(1..1000));
+        let instruction = match rng.gen_range(0..10) {
+            0 => "do()".to_string(),
+            1 => "don't()".to_string(),
+            2 => format!("mul({},{}", a, b),
+            3 => format!("mul ( {},{})", a, b),
+            4 => words.choose(rng).unwrap().to_string(),
+            _ => format!("mul({},{})", a, b),
+        };
+        memory.push_str(&instruction);
+        if i % 100 == 99 {
+            memory.push('\n');
+        }
+    }
+    memory.trim_end().to_string()
+}
+
+// size: side of the word search
+pub fn day04(rng: &mut StdRng, size: usize) -> String {
+    grid_to_string(&char_grid(rng, size, &['X', 'M', 'A', 'S']))
+}
+
+// size: number of distinct page numbers
+pub fn day05(rng: &mut StdRng, size: usize) -> String {
+    let size = size.clamp(3, 89);
+    let mut pages = (10..100).collect::<Vec<u32>>();
+    pages.shuffle(rng);
+    pages.truncate(size);
+    let mut rules = vec![];
+    for (i, a) in pages.iter().enumerate() {
+        for b in pages[i + 1..].iter() {
+            rules.push(format!("{}|{}", a, b));
+        }
+    }
+    rules.shuffle(rng);
+    let updates = (0..size * 4).map(|_| {
+        let len = rng.gen_range(1..=size.min(23).div_ceil(2)) * 2 - 1;
+        let mut indices = rand::seq::index::sample(rng, size, len).into_vec();
+        if rng.gen_bool(0.5) {
+            indices.sort();
+        }
+        indices
+            .iter()
+            .map(|&i| pages[i].to_string())
+            .collect::<Vec<_>>()
+            .join(",")
+    });
+    format!("{}\n\n{}", lines(rules), lines(updates))
+}
+
+// size: side of the lab
+pub fn day06(rng: &mut StdRng, size: usize) -> String {
+    let size = size.max(2);
+    // the guard has to walk off the map in part 1, so retry until they do
+    loop {
+        let mut grid = (0..size)
+            .map(|_| {
+                (0..size)
+                    .map(|_| if rng.gen_bool(0.05) { '#' } else { '.' })
+                    .collect()
+            })
+            .collect::<Vec<Vec<char>>>();
+        let (i, j) = (rng.gen_range(0..size), rng.gen_range(0..size));
+        grid[i][j] = '^';
+        if day06_guard_leaves(&grid, (i as i32, j as i32)) {
+            return grid_to_string(&grid);
+        }
+    }
+}
+
+fn day06_guard_leaves(grid: &[Vec<char>], start: (i32, i32)) -> bool {
+    let n = grid.len() as i32;
+    let (mut pos, mut dir) = (start, (-1, 0));
+    let mut seen: HashSet<((i32, i32), (i32, i32))> = HashSet::new();
+    while seen.insert((pos, dir)) {
+        let next = (pos.0 + dir.0, pos.1 + dir.1);
+        if next.0 < 0 || next.0 >= n || next.1 < 0 || next.1 >= n {
+            return true;
+        }
+        if grid[next.0 as usize][next.1 as usize] == '#' {
+            dir = (dir.1, -dir.0);
+        } else {
+            pos = next;
+        }
+    }
+    false
+}
+
+// size: number of equations
+pub fn day07(rng: &mut StdRng, size: usize) -> String {
+    lines((0..size).map(|_| {
+        // real targets have at most 15 digits, so the answers fit in a u64
+        // too; draw again until this one does
+        let (operands, target) = loop {
+            let operands = (0..rng.gen_range(2..=8))
+                .map(|_| rng.gen_range(1..1000u64))
+                .collect::<Vec<_>>();
+            let mut target = Some(operands[0]);
+            for &o in operands[1..].iter() {
+                target = target.and_then(|t| match rng.gen_range(0..3) {
+                    0 => t.checked_add(o),
+                    1 => t.checked_mul(o),
+                    _ => t.checked_mul(10u64.pow(o.ilog10() + 1))?.checked_add(o),
+                });
+            }
+            if rng.gen_bool(0.3) {
+                target = target.and_then(|t| t.checked_add(1));
+            }
+            if let Some(target) = target.filter(|&t| t < 10u64.pow(15)) {
+                break (operands, target);
+            }
+        };
+        let operands = operands
+            .iter()
+            .map(|o| o.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        format!("{}: {}", target, operands)
+    }))
+}
+
+// size: side of the map
+pub fn day08(rng: &mut StdRng, size: usize) -> String {
+    let size = size.max(1);
+    let frequencies = ('a'..='z')
+        .chain('A'..='Z')
+        .chain('0'..='9')
+        .collect::<Vec<_>>();
+    let mut grid = vec![vec!['.'; size]; size];
+    for &f in frequencies.choose_multiple(rng, (size / 2).max(1)) {
+        for _ in 0..rng.gen_range(2..=4) {
+            let (i, j) = (rng.gen_range(0..size), rng.gen_range(0..size));
+            grid[i][j] = f;
+        }
+    }
+    grid_to_string(&grid)
+}
+
+// size: number of files
+pub fn day09(rng: &mut StdRng, size: usize) -> String {
+    let mut disk_map = String::new();
+    for i in 0..size.max(1) {
+        if i > 0 {
+            disk_map.push_str(&rng.gen_range(0..10).to_string());
+        }
+        disk_map.push_str(&rng.gen_range(1..10).to_string());
+    }
+    disk_map
+}
+
+// size: side of the topographic map
+pub fn day10(rng: &mut StdRng, size: usize) -> String {
+    let size = size.max(2);
+    let mut grid = (0..size)
+        .map(|_| (0..size).map(|_| rng.gen_range(0..10u8)).collect())
+        .collect::<Vec<Vec<u8>>>();
+    // plant uphill walks from 0 to 9 so there are trails to find
+    for _ in 0..size * size / 20 {
+        let (mut i, mut j) = (rng.gen_range(0..size), rng.gen_range(0..size));
+        grid[i][j] = 0;
+        for h in 1..10 {
+            let (i2, j2) = match rng.gen_range(0..4) {
+                0 => (i.saturating_sub(1), j),
+                1 => ((i + 1).min(size - 1), j),
+                2 => (i, j.saturating_sub(1)),
+                _ => (i, (j + 1).min(size - 1)),
+            };
+            if (i2, j2) == (i, j) {
+                break;
+            }
+            (i, j) = (i2, j2);
+            grid[i][j] = h;
+        }
+    }
+    lines(
+        grid.iter()
+            .map(|row| row.iter().map(|h| h.to_string()).collect()),
+    )
+}
+
+// size: number of stones
+pub fn day11(rng: &mut StdRng, size: usize) -> String {
+    (0..size.max(1))
+        .map(|_| rng.gen_range(0..10_000_000).to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// size: side of the garden
+pub fn day12(rng: &mut StdRng, size: usize) -> String {
+    let letters = ('A'..='Z').collect::<Vec<_>>();
+    let mut grid = char_grid(rng, size, &letters);
+    // copy neighbouring plants around so regions grow beyond single plots
+    for _ in 0..size * size * 4 {
+        let (i, j) = (rng.gen_range(0..size), rng.gen_range(0..size));
+        let (i2, j2) = match rng.gen_range(0..4) {
+            0 => (i.saturating_sub(1), j),
+            1 => ((i + 1).min(size - 1), j),
+            2 => (i, j.saturating_sub(1)),
+            _ => (i, (j + 1).min(size - 1)),
+        };
+        grid[i][j] = grid[i2][j2];
+    }
+    grid_to_string(&grid)
+}
+
+// size: number of claw machines
+pub fn day13(rng: &mut StdRng, size: usize) -> String {
+    let machines = (0..size).map(|_| {
+        let a = (rng.gen_range(10..100), rng.gen_range(10..100));
+        let b = (rng.gen_range(10..100), rng.gen_range(10..100));
+        let prize = if rng.gen_bool(0.5) {
+            let (n_a, n_b) = (rng.gen_range(0..100), rng.gen_range(0..100));
+            (a.0 * n_a + b.0 * n_b, a.1 * n_a + b.1 * n_b)
+        } else {
+            (rng.gen_range(1000..20000), rng.gen_range(1000..20000))
+        };
+        format!(
+            "Button A: X+{}, Y+{}\nButton B: X+{}, Y+{}\nPrize: X={}, Y={}",
+            a.0, a.1, b.0, b.1, prize.0, prize.1
+        )
+    });
+    machines.collect::<Vec<_>>().join("\n\n")
+}
+
+// size: number of robots, in the default 101x103 room
+pub fn day14(rng: &mut StdRng, size: usize) -> String {
+    lines((0..size).map(|_| {
+        format!(
+            "p={},{} v={},{}",
+            rng.gen_range(0..101),
+            rng.gen_range(0..103),
+            rng.gen_range(-100..=100),
+            rng.gen_range(-100..=100)
+        )
+    }))
+}
+
+// size: side of the warehouse
+pub fn day15(rng: &mut StdRng, size: usize) -> String {
+    let size = size.max(4);
+    let mut grid = vec![vec!['#'; size]; size];
+    for row in grid[1..size - 1].iter_mut() {
+        for c in row[1..size - 1].iter_mut() {
+            *c = match rng.gen_range(0..10) {
+                0 => '#',
+                1..=3 => 'O',
+                _ => '.',
+            };
+        }
+    }
+    let (i, j) = (rng.gen_range(1..size - 1), rng.gen_range(1..size - 1));
+    grid[i][j] = '@';
+    let moves = (0..size * size * 8)
+        .map(|_| *['<', '>', '^', 'v'].choose(rng).unwrap())
+        .collect::<Vec<_>>();
+    let moves = lines(moves.chunks(70).map(|chunk| chunk.iter().collect()));
+    format!("{}\n\n{}", grid_to_string(&grid), moves)
+}
+
+// size: side of the maze
+pub fn day16(rng: &mut StdRng, size: usize) -> String {
+    let mut maze = maze::perfect_maze(rng, size);
+    let n = maze.len();
+    // knock out some walls so there is more than one best path
+    for _ in 0..n * n / 20 {
+        let (i, j) = (rng.gen_range(1..n - 1), rng.gen_range(1..n - 1));
+        maze[i][j] = '.';
+    }
+    maze[n - 2][1] = 'S';
+    maze[1][n - 2] = 'E';
+    maze::to_string(&maze)
+}
+
+// size: number of 3-bit digits in register A. The program always has the
+// usual shape, with constants picked so that a quine exists.
+pub fn day17(rng: &mut StdRng, size: usize) -> String {
+    let size = size.clamp(1, 20) as u32;
+    let a = rng.gen_range(8u64.pow(size - 1)..8u64.pow(size));
+    loop {
+        let (x, y) = (rng.gen_range(0..8), rng.gen_range(0..8));
+        let program = [
+            2,
+            4,
+            1,
+            x,
+            7,
+            5,
+            1,
+            y,
+            4,
+            rng.gen_range(0..8),
+            5,
+            5,
+            0,
+            3,
+            3,
+            0,
+        ];
+        if day17_has_quine(&program, x, y) {
+            let program = program.map(|v| v.to_string()).join(",");
+            return format!(
+                "Register A: {}\nRegister B: 0\nRegister C: 0\n\nProgram: {}",
+                a, program
+            );
+        }
+    }
+}
+
+fn day17_has_quine(program: &[u64], x: u64, y: u64) -> bool {
+    let output = |a: u64| {
+        let b = (a % 8) ^ x;
+        (b ^ y ^ (a >> b)) % 8
+    };
+    // each loop iteration consumes the lowest 3 bits of A, so build A up from
+    // the last output backwards
+    let mut candidates = vec![0u64];
+    for &target in program.iter().rev() {
+        candidates = candidates
+            .iter()
+            .flat_map(|&a| (0..8).map(move |d| (a << 3) | d))
+            .filter(|&a| a != 0 && output(a) == target)
+            .collect();
+    }
+    !candidates.is_empty()
+}
+
+// size: side of the memory space. Part 1 drops 12 bytes (a kilobyte once
+// there are more) and only (size - 1)^2 of them miss the kept route, so a
+// side below 5 can't give it enough.
+pub fn day18(rng: &mut StdRng, size: usize) -> String {
+    let size = size.max(5) as i32;
+    let mut bytes = (0..size)
+        .flat_map(|x| (0..size).map(move |y| (x, y)))
+        .filter(|&p| p != (0, 0) && p != (size - 1, size - 1))
+        .collect::<Vec<_>>();
+    bytes.shuffle(rng);
+    // keep one route open until the end so part 1's bytes never block it
+    let mut route: HashSet<(i32, i32)> = HashSet::new();
+    let (mut x, mut y) = (0, 0);
+    while (x, y) != (size - 1, size - 1) {
+        if y == size - 1 || (x < size - 1 && rng.gen_bool(0.5)) {
+            x += 1;
+        } else {
+            y += 1;
+        }
+        route.insert((x, y));
+    }
+    let (mut open, late): (Vec<_>, Vec<_>) = bytes.into_iter().partition(|p| !route.contains(p));
+    open.extend(late);
+    lines(open.iter().map(|(x, y)| format!("{},{}", x, y)))
+}
+
+// size: number of designs
+pub fn day19(rng: &mut StdRng, size: usize) -> String {
+    let colours = ['w', 'u', 'b', 'r', 'g'];
+    let mut towels: Vec<String> = vec![];
+    for _ in 0..size.max(10) {
+        let len = rng.gen_range(1..=8);
+        let towel = (0..len).map(|_| *colours.choose(rng).unwrap()).collect();
+        if !towels.contains(&towel) {
+            towels.push(towel);
+        }
+    }
+    let designs = (0..size).map(|_| {
+        let len = rng.gen_range(20..=60);
+        let mut design = String::new();
+        if rng.gen_bool(0.2) {
+            design = (0..len).map(|_| *colours.choose(rng).unwrap()).collect();
+        } else {
+            while design.len() < len {
+                design.push_str(towels.choose(rng).unwrap());
+            }
+        }
+        design
+    });
+    format!("{}\n\n{}", towels.join(", "), lines(designs))
+}
+
+// size: side of the racetrack
+pub fn day20(rng: &mut StdRng, size: usize) -> String {
+    // walling off everything but the one S-E path of a perfect maze leaves a
+    // single track with no branches
+    let maze = maze::perfect_maze(rng, size);
+    let n = maze.len();
+    let (start, goal) = ((n - 2, 1), (1, n - 2));
+    let track = maze::path(&maze, start, goal);
+    let mut grid = vec![vec!['#'; n]; n];
+    for &(i, j) in track.iter() {
+        grid[i][j] = '.';
+    }
+    grid[start.0][start.1] = 'S';
+    grid[goal.0][goal.1] = 'E';
+    grid_to_string(&grid)
+}
+
+// size: number of door codes
+pub fn day21(rng: &mut StdRng, size: usize) -> String {
+    lines((0..size).map(|_| format!("{:03}A", rng.gen_range(1..1000))))
+}
+
+// size: number of buyers
+pub fn day22(rng: &mut StdRng, size: usize) -> String {
+    lines((0..size).map(|_| rng.gen_range(1..16777216).to_string()))
+}
+
+// size: number of computers (at most 26 * 26)
+pub fn day23(rng: &mut StdRng, size: usize) -> String {
+    let mut names = ('a'..='z')
+        .flat_map(|a| ('a'..='z').map(move |b| format!("{}{}", a, b)))
+        .collect::<Vec<_>>();
+    names.shuffle(rng);
+    names.truncate(size.clamp(3, names.len()));
+    let mut edges: HashSet<(usize, usize)> = HashSet::new();
+    let mut add_edge = |a: usize, b: usize| {
+        if a != b {
+            edges.insert((a.min(b), a.max(b)));
+        }
+    };
+    // a planted LAN party, plus random links between everyone
+    let party = rand::seq::index::sample(rng, names.len(), names.len().min(13)).into_vec();
+    for &a in party.iter() {
+        for &b in party.iter() {
+            add_edge(a, b);
+        }
+    }
+    for _ in 0..names.len() * 6 {
+        add_edge(rng.gen_range(0..names.len()), rng.gen_range(0..names.len()));
+    }
+    let mut edges = edges.into_iter().collect::<Vec<_>>();
+    edges.sort();
+    edges.shuffle(rng);
+    lines(edges.iter().map(|&(a, b)| {
+        if rng.gen_bool(0.5) {
+            format!("{}-{}", names[a], names[b])
+        } else {
+            format!("{}-{}", names[b], names[a])
+        }
+    }))
+}
+
+// size: number of input bits of a ripple-carry adder, with four pairs of
+// gate outputs swapped as in the puzzle
+pub fn day24(rng: &mut StdRng, size: usize) -> String {
+    // four swaps, each in a different bit strictly between the first and last
+    let bits = size.max(6);
+    let mut used: HashSet<String> = HashSet::new();
+    let mut wire = |rng: &mut StdRng| loop {
+        let name = (0..3)
+            .map(|_| rng.gen_range(b'a'..=b'w') as char)
+            .collect::<String>();
+        if used.insert(name.clone()) {
+            return name;
+        }
+    };
+    // (a, op, b, dest)
+    let mut gates: Vec<(String, &str, String, String)> = vec![];
+    let mut carry = String::new();
+    for i in 0..bits {
+        let (x, y, z) = (
+            format!("x{:02}", i),
+            format!("y{:02}", i),
+            format!("z{:02}", i),
+        );
+        if i == 0 {
+            carry = wire(rng);
+            gates.push((x.clone(), "XOR", y.clone(), z));
+            gates.push((x, "AND", y, carry.clone()));
+            continue;
+        }
+        let (sum, and1, and2, carry_out) = (wire(rng), wire(rng), wire(rng), wire(rng));
+        let carry_out = if i == bits - 1 {
+            format!("z{:02}", bits)
+        } else {
+            carry_out
+        };
+        gates.push((x.clone(), "XOR", y.clone(), sum.clone()));
+        gates.push((x, "AND", y, and1.clone()));
+        gates.push((sum.clone(), "XOR", carry.clone(), z));
+        gates.push((sum, "AND", carry.clone(), and2.clone()));
+        gates.push((and1, "OR", and2, carry_out.clone()));
+        carry = carry_out;
+    }
+
+    // swap outputs within a single full adder, avoiding cycles
+    let mut swapped_bits: HashSet<usize> = HashSet::new();
+    while swapped_bits.len() < 4 {
+        let bit = rng.gen_range(1..bits - 1);
+        if swapped_bits.contains(&bit) {
+            continue;
+        }
+        let first = 2 + (bit - 1) * 5;
+        let mut pair = rand::seq::index::sample(rng, 5, 2).into_vec();
+        pair.sort();
+        // the two ANDs both feed the OR, so swapping them changes nothing
+        if pair == [1, 3] {
+            continue;
+        }
+        let (g1, g2) = (first + pair[0], first + pair[1]);
+        let (d1, d2) = (gates[g1].3.clone(), gates[g2].3.clone());
+        gates[g1].3 = d2.clone();
+        gates[g2].3 = d1.clone();
+        if day24_is_acyclic(&gates) {
+            swapped_bits.insert(bit);
+        } else {
+            gates[g1].3 = d1;
+            gates[g2].3 = d2;
+        }
+    }
+
+    // x wires first, then y wires, as in the puzzle input
+    let mut inputs = (0..bits)
+        .flat_map(|i| {
+            let (x, y) = (rng.gen_range(0..2), rng.gen_range(0..2));
+            [format!("x{:02}: {}", i, x), format!("y{:02}: {}", i, y)]
+        })
+        .collect::<Vec<_>>();
+    inputs.sort();
+    gates.shuffle(rng);
+    let gates = gates.iter().map(|(a, op, b, dest)| {
+        if rng.gen_bool(0.5) {
+            format!("{} {} {} -> {}", a, op, b, dest)
+        } else {
+            format!("{} {} {} -> {}", b, op, a, dest)
+        }
+    });
+    format!("{}\n\n{}", lines(inputs), lines(gates.collect::<Vec<_>>()))
+}
+
+fn day24_is_acyclic(gates: &[(String, &str, String, String)]) -> bool {
+    let drivers: HashMap<&str, usize> = gates
+        .iter()
+        .enumerate()
+        .map(|(i, g)| (g.3.as_str(), i))
+        .collect();
+    // 0 = unvisited, 1 = in progress, 2 = done
+    let mut state = vec![0u8; gates.len()];
+    fn visit(
+        i: usize,
+        gates: &[(String, &str, String, String)],
+        drivers: &HashMap<&str, usize>,
+        state: &mut Vec<u8>,
+    ) -> bool {
+        match state[i] {
+            1 => return false,
+            2 => return true,
+            _ => {}
+        }
+        state[i] = 1;
+        for input in [&gates[i].0, &gates[i].2] {
+            if let Some(&j) = drivers.get(input.as_str()) {
+                if !visit(j, gates, drivers, state) {
+                    return false;
+                }
+            }
+        }
+        state[i] = 2;
+        true
+    }
+    (0..gates.len()).all(|i| visit(i, gates, &drivers, &mut state))
+}
+
+// size: number of schematics, locks and keys mixed
+pub fn day25(rng: &mut StdRng, size: usize) -> String {
+    let schematics = (0..size).map(|_| {
+        let is_lock = rng.gen_bool(0.5);
+        let heights = (0..5).map(|_| rng.gen_range(0..=5)).collect::<Vec<usize>>();
+        let rows = (0..7).map(|r| {
+            heights
+                .iter()
+                .map(|&h| {
+                    let filled = if is_lock { r <= h } else { r >= 6 - h };
+                    if filled {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect::<String>()
+        });
+        lines(rows.collect::<Vec<_>>())
+    });
+    schematics.collect::<Vec<_>>().join("\n\n")
+}
@@ -0,0 +1,70 @@
+use rand::{rngs::StdRng, seq::SliceRandom};
+use std::collections::{HashMap, VecDeque};
+
+pub type Maze = Vec<Vec<char>>;
+
+// Walls everywhere except a spanning tree over the odd (i, j) cells, carved by
+// a randomised depth-first search. `n` is rounded up to an odd number >= 5.
+pub fn perfect_maze(rng: &mut StdRng, n: usize) -> Maze {
+    let n = n.max(5) | 1;
+    let mut maze = vec![vec!['#'; n]; n];
+    let mut stack = vec![(1, 1)];
+    maze[1][1] = '.';
+    while let Some(&(i, j)) = stack.last() {
+        let mut options: Vec<(usize, usize)> = vec![];
+        if i > 2 {
+            options.push((i - 2, j));
+        }
+        if i + 2 < n - 1 {
+            options.push((i + 2, j));
+        }
+        if j > 2 {
+            options.push((i, j - 2));
+        }
+        if j + 2 < n - 1 {
+            options.push((i, j + 2));
+        }
+        options.retain(|&(i2, j2)| maze[i2][j2] == '#');
+        match options.choose(rng) {
+            Some(&(i2, j2)) => {
+                maze[(i + i2) / 2][(j + j2) / 2] = '.';
+                maze[i2][j2] = '.';
+                stack.push((i2, j2));
+            }
+            None => {
+                stack.pop();
+            }
+        }
+    }
+    maze
+}
+
+// Cells on the (unique, in a perfect maze) path from `start` to `goal`
+pub fn path(maze: &Maze, start: (usize, usize), goal: (usize, usize)) -> Vec<(usize, usize)> {
+    let mut prev: HashMap<(usize, usize), (usize, usize)> = HashMap::from([(start, start)]);
+    let mut queue = VecDeque::from([start]);
+    while let Some((i, j)) = queue.pop_front() {
+        if (i, j) == goal {
+            break;
+        }
+        for next in [(i - 1, j), (i + 1, j), (i, j - 1), (i, j + 1)] {
+            if maze[next.0][next.1] != '#' && !prev.contains_key(&next) {
+                prev.insert(next, (i, j));
+                queue.push_back(next);
+            }
+        }
+    }
+    let mut path = vec![goal];
+    while *path.last().unwrap() != start {
+        path.push(prev[path.last().unwrap()]);
+    }
+    path.reverse();
+    path
+}
+
+pub fn to_string(maze: &Maze) -> String {
+    maze.iter()
+        .map(|row| row.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,62 @@
+use rand::{rngs::StdRng, SeedableRng};
+
+mod days;
+mod maze;
+
+const DEFAULT_SEED: u64 = 0;
+
+pub type Generator = fn(&mut StdRng, usize) -> String;
+
+// (generator, default size) per day; what "size" means is up to each day
+static GENS: [(Generator, usize); 25] = [
+    (days::day01, 1000),
+    (days::day02, 1000),
+    (days::day03, 700),
+    (days::day04, 140),
+    (days::day05, 49),
+    (days::day06, 130),
+    (days::day07, 850),
+    (days::day08, 50),
+    (days::day09, 10000),
+    (days::day10, 50),
+    (days::day11, 8),
+    (days::day12, 140),
+    (days::day13, 320),
+    (days::day14, 500),
+    (days::day15, 50),
+    (days::day16, 141),
+    (days::day17, 16),
+    (days::day18, 71),
+    (days::day19, 400),
+    (days::day20, 141),
+    (days::day21, 5),
+    (days::day22, 2000),
+    (days::day23, 520),
+    (days::day24, 45),
+    (days::day25, 500),
+];
+
+pub fn generate(day: usize, seed: u64, size: Option<usize>) -> String {
+    let (generator, default_size) = GENS[day - 1];
+    let mut rng = StdRng::seed_from_u64(seed);
+    generator(&mut rng, size.unwrap_or(default_size))
+}
+
+// aoc gen <day> [--seed S] [--size N]
+pub fn main(args: Vec<String>) {
+    let day = args[0].parse::<usize>().unwrap();
+    assert!((1..=25).contains(&day), "Invalid day: {}", day);
+    let mut seed = DEFAULT_SEED;
+    let mut size = None;
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--seed" => seed = args[i + 1].parse().unwrap(),
+            "--size" => size = Some(args[i + 1].parse().unwrap()),
+            arg => panic!("Invalid argument: {}", arg),
+        }
+        i += 2;
+    }
+    // no trailing newline, like the checked-in inputs
+    print!("{}", generate(day, seed, size));
+}
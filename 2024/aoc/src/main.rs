@@ -1,5 +1,6 @@
 use std::env;
 
+mod check;
 mod day01;
 mod day02;
 mod day03;
@@ -25,7 +26,10 @@ mod day22;
 mod day23;
 mod day24;
 mod day25;
+mod gen;
+mod repl;
 mod util;
+mod watch;
 
 static FNS: [fn(Vec<String>); 25] = [
     day01::main,
@@ -57,6 +61,14 @@ static FNS: [fn(Vec<String>); 25] = [
 
 fn main() {
     let args: Vec<String> = env::args().collect();
-    let day = args[1].parse::<usize>().unwrap();
-    FNS[day - 1](args.clone().into_iter().skip(2).collect());
+    match args[1].as_str() {
+        "gen" => gen::main(args.into_iter().skip(2).collect()),
+        "check" => check::main(args.into_iter().skip(2).collect()),
+        "repl" => repl::main(args.into_iter().skip(2).collect()),
+        "watch" => watch::main(args.into_iter().skip(2).collect()),
+        _ => {
+            let day = args[1].parse::<usize>().unwrap();
+            FNS[day - 1](args.clone().into_iter().skip(2).collect());
+        }
+    }
 }
@@ -0,0 +1,85 @@
+use crate::{day12, day17, day22, day24};
+use std::io::{self, BufRead, Write};
+
+// A day's parsed input, kept around between commands
+pub trait Session {
+    fn part1(&self) -> String;
+
+    fn part2(&self) -> String;
+
+    // (usage, description) of each day-specific command, for `help`
+    fn commands(&self) -> Vec<(&'static str, &'static str)>;
+
+    // None if `command` isn't one of this day's commands
+    fn run(&mut self, command: &str, args: &[&str]) -> Option<Result<String, String>>;
+}
+
+fn session(day: usize, args: Vec<String>) -> Option<Box<dyn Session>> {
+    match day {
+        12 => Some(day12::session(args)),
+        17 => Some(day17::session(args)),
+        22 => Some(day22::session(args)),
+        24 => Some(day24::session(args)),
+        _ => None,
+    }
+}
+
+// Parses one argument, with the error a command can pass straight back
+pub fn arg<T: std::str::FromStr>(args: &[&str], i: usize, name: &str) -> Result<T, String> {
+    let s = args.get(i).ok_or(format!("missing <{}>", name))?;
+    s.parse().map_err(|_| format!("invalid <{}>: {}", name, s))
+}
+
+fn help(session: &dyn Session) -> String {
+    let mut commands = vec![
+        ("part1", "solve part 1"),
+        ("part2", "solve part 2"),
+        ("help", "show this list"),
+        ("quit", "leave the repl"),
+    ];
+    commands.extend(session.commands());
+    let width = commands.iter().map(|(usage, _)| usage.len()).max().unwrap();
+    commands
+        .iter()
+        .map(|(usage, description)| format!("{:width$}  {}", usage, description))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// aoc repl <day> [input file]
+pub fn main(args: Vec<String>) {
+    let day = args[0].parse::<usize>().unwrap();
+    assert!((1..=25).contains(&day), "Invalid day: {}", day);
+    let Some(mut session) = session(day, args.into_iter().skip(1).collect()) else {
+        println!("No repl for day {}; try 12, 17, 22 or 24", day);
+        return;
+    };
+    println!("Day {} loaded, `help` lists commands", day);
+
+    let stdin = io::stdin();
+    loop {
+        print!("> ");
+        io::stdout().flush().unwrap();
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).unwrap() == 0 {
+            break;
+        }
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        let Some((&command, args)) = words.split_first() else {
+            continue;
+        };
+        let result = match command {
+            "part1" => Ok(session.part1()),
+            "part2" => Ok(session.part2()),
+            "help" => Ok(help(session.as_ref())),
+            "quit" | "exit" => break,
+            _ => session
+                .run(command, args)
+                .unwrap_or(Err(format!("unknown command: {}", command))),
+        };
+        match result {
+            Ok(output) => println!("{}", output),
+            Err(message) => println!("error: {}", message),
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use std::{collections::HashMap, hash::Hash};
+
+// States `tail..tail + period` repeat forever once the simulation reaches them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub tail: usize,
+    pub period: usize,
+}
+
+#[allow(unused)]
+impl Cycle {
+    // Index within the first lap that is equivalent to step `n`
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.tail {
+            n
+        } else {
+            self.tail + (n - self.tail) % self.period
+        }
+    }
+}
+
+// Hashed history. `step` returns None once the simulation terminates, in which
+// case there is no cycle.
+#[allow(unused)]
+pub fn find_cycle<S, F>(start: S, mut step: F) -> Option<Cycle>
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> Option<S>,
+{
+    let mut seen: HashMap<S, usize> = HashMap::new();
+    let mut state = start;
+    let mut i = 0;
+    loop {
+        if let Some(&first) = seen.get(&state) {
+            return Some(Cycle {
+                tail: first,
+                period: i - first,
+            });
+        }
+        let next = step(&state)?;
+        seen.insert(state, i);
+        state = next;
+        i += 1;
+    }
+}
+
+// Brent's algorithm; constant memory, but `step` must never terminate
+#[allow(unused)]
+pub fn brent<S, F>(start: S, mut step: F) -> Cycle
+where
+    S: Clone + Eq,
+    F: FnMut(&S) -> S,
+{
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+    }
+
+    let mut tortoise = start.clone();
+    let mut hare = start;
+    for _ in 0..period {
+        hare = step(&hare);
+    }
+    let mut tail = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        tail += 1;
+    }
+    Cycle { tail, period }
+}
+
+// State after `n` steps, jumping ahead once a repeat is found. Returns None if
+// the simulation terminates before step `n`.
+#[allow(unused)]
+pub fn state_after<S, F>(start: S, mut step: F, n: usize) -> Option<S>
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> Option<S>,
+{
+    let mut seen: HashMap<S, usize> = HashMap::new();
+    let mut history: Vec<S> = vec![];
+    let mut state = start;
+    for i in 0..n {
+        if let Some(&first) = seen.get(&state) {
+            let cycle = Cycle {
+                tail: first,
+                period: i - first,
+            };
+            return Some(history.swap_remove(cycle.reduce(n)));
+        }
+        let next = step(&state)?;
+        seen.insert(state.clone(), i);
+        history.push(state);
+        state = next;
+    }
+    Some(state)
+}
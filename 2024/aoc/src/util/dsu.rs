@@ -0,0 +1,170 @@
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone)]
+pub struct Dsu {
+    parent: Vec<usize>,
+    rank: Vec<u8>,
+    size: Vec<usize>,
+}
+
+#[allow(unused)]
+impl Dsu {
+    pub fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            rank: vec![0; n],
+            size: vec![1; n],
+        }
+    }
+
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut x = x;
+        while self.parent[x] != root {
+            let next = self.parent[x];
+            self.parent[x] = root;
+            x = next;
+        }
+        root
+    }
+
+    // Returns false if `a` and `b` were already connected
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.rank[a] < self.rank[b] {
+            (a, b) = (b, a);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        if self.rank[a] == self.rank[b] {
+            self.rank[a] += 1;
+        }
+        true
+    }
+
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    pub fn size_of(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+}
+
+// Connectivity of the open cells of a grid as cells are blocked over time.
+// Union-find can only merge, so unblocking is cheap while blocking rebuilds
+// the sets; replay blocks in reverse (see `first_disconnect`) to stay fast.
+pub struct GridConnectivity {
+    ids: HashMap<(i32, i32), usize>,
+    blocked: HashSet<(i32, i32)>,
+    dsu: Dsu,
+}
+
+#[allow(unused)]
+impl GridConnectivity {
+    pub fn new(
+        cells: impl IntoIterator<Item = (i32, i32)>,
+        blocked: impl IntoIterator<Item = (i32, i32)>,
+    ) -> Self {
+        let ids: HashMap<(i32, i32), usize> = cells
+            .into_iter()
+            .enumerate()
+            .map(|(id, cell)| (cell, id))
+            .collect();
+        let mut gc = Self {
+            dsu: Dsu::new(ids.len()),
+            ids,
+            blocked: blocked.into_iter().collect(),
+        };
+        gc.rebuild();
+        gc
+    }
+
+    pub fn block(&mut self, cell: (i32, i32)) {
+        if self.ids.contains_key(&cell) && self.blocked.insert(cell) {
+            self.rebuild();
+        }
+    }
+
+    pub fn unblock(&mut self, cell: (i32, i32)) {
+        if self.blocked.remove(&cell) && self.ids.contains_key(&cell) {
+            self.join_neighbors(cell);
+        }
+    }
+
+    pub fn is_blocked(&self, cell: (i32, i32)) -> bool {
+        self.blocked.contains(&cell)
+    }
+
+    pub fn connected(&mut self, a: (i32, i32), b: (i32, i32)) -> bool {
+        if self.is_blocked(a) || self.is_blocked(b) {
+            return false;
+        }
+        match (self.ids.get(&a), self.ids.get(&b)) {
+            (Some(&a), Some(&b)) => self.dsu.connected(a, b),
+            _ => false,
+        }
+    }
+
+    fn rebuild(&mut self) {
+        self.dsu = Dsu::new(self.ids.len());
+        let cells = self.ids.keys().cloned().collect::<Vec<_>>();
+        for cell in cells {
+            self.join_neighbors(cell);
+        }
+    }
+
+    fn join_neighbors(&mut self, cell: (i32, i32)) {
+        if self.is_blocked(cell) {
+            return;
+        }
+        let id = self.ids[&cell];
+        let (i, j) = cell;
+        for neighbor in [(i - 1, j), (i + 1, j), (i, j - 1), (i, j + 1)] {
+            if self.is_blocked(neighbor) {
+                continue;
+            }
+            if let Some(&other) = self.ids.get(&neighbor) {
+                self.dsu.union(id, other);
+            }
+        }
+    }
+}
+
+// Index of the first cell in `blocks` after which `start` and `goal` are no
+// longer connected. None if they are still connected after every block, or
+// were never connected to begin with.
+#[allow(unused)]
+pub fn first_disconnect(
+    cells: impl IntoIterator<Item = (i32, i32)>,
+    blocks: &[(i32, i32)],
+    start: (i32, i32),
+    goal: (i32, i32),
+) -> Option<usize> {
+    let mut gc = GridConnectivity::new(cells, blocks.iter().cloned());
+    if gc.connected(start, goal) {
+        return None;
+    }
+    // a cell blocked more than once only opens up again at its first block
+    let mut first_block: HashMap<(i32, i32), usize> = HashMap::new();
+    for (i, &cell) in blocks.iter().enumerate() {
+        first_block.entry(cell).or_insert(i);
+    }
+    for (i, &cell) in blocks.iter().enumerate().rev() {
+        if first_block[&cell] != i {
+            continue;
+        }
+        gc.unblock(cell);
+        if gc.connected(start, goal) {
+            return Some(i);
+        }
+    }
+    None
+}
@@ -0,0 +1,47 @@
+// Inputs compiled into the binary, for running away from the source tree.
+// Only built with the `embed-inputs` feature, so personal inputs aren't part
+// of every build. New input files need adding here.
+
+macro_rules! embed {
+    ($($day:literal: [$($file:literal),* $(,)?]),* $(,)?) => {
+        static FILES: &[(&str, &str, &str)] = &[
+            $($(($day, $file, include_str!(concat!("../", $day, "/", $file))),)*)*
+        ];
+    };
+}
+
+embed! {
+    "day01": ["input.txt", "input.txt.example"],
+    "day02": ["input.txt", "input.txt.example"],
+    "day03": ["input.txt", "input.txt.example", "input.txt.example.2"],
+    "day04": ["input.txt", "input.txt.example"],
+    "day05": ["input.txt", "input.txt.example"],
+    "day06": ["input.txt", "input.txt.example"],
+    "day07": ["input.txt", "input.txt.example"],
+    "day08": ["input.txt", "input.txt.example"],
+    "day09": ["input.txt", "input.txt.example"],
+    "day10": ["input.txt", "input.txt.example"],
+    "day11": ["input.txt", "input.txt.example"],
+    "day12": ["input.txt", "input.txt.example.1", "input.txt.example.2"],
+    "day13": ["input.txt", "input.txt.example"],
+    "day14": ["input.txt", "input.txt.example"],
+    "day15": ["input.txt", "input.txt.example"],
+    "day16": ["input.txt", "input.txt.example"],
+    "day17": ["input.txt", "input.txt.example.1", "input.txt.example.2", "input.txt.example.3"],
+    "day18": ["input.txt", "input.txt.example"],
+    "day19": ["input.txt", "input.txt.example"],
+    "day20": ["input.txt", "input.txt.example"],
+    "day21": ["input.txt", "input.txt.example"],
+    "day22": ["input.txt", "input.txt.example.1", "input.txt.example.2"],
+    "day23": ["input.txt", "input.txt.example"],
+    "day24": ["input.txt", "input.txt.example.1", "input.txt.example.2"],
+    "day25": ["input.txt", "input.txt.example"],
+}
+
+// Contents of `file_name` from `day` (e.g. "day01")
+pub fn get(day: &str, file_name: &str) -> Option<&'static str> {
+    FILES
+        .iter()
+        .find(|(d, f, _)| *d == day && *f == file_name)
+        .map(|(_, _, text)| *text)
+}
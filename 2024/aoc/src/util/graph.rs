@@ -0,0 +1,195 @@
+use crate::util::dsu::Dsu;
+use std::collections::{HashMap, HashSet};
+
+// Undirected graph over interned string node names
+#[derive(Debug, Clone, Default)]
+pub struct Graph {
+    names: Vec<String>,
+    ids: HashMap<String, usize>,
+    adj: Vec<HashSet<usize>>,
+}
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DegreeStats {
+    pub min: usize,
+    pub max: usize,
+    pub mean: f64,
+}
+
+#[allow(unused)]
+impl Graph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn intern(&mut self, name: &str) -> usize {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id = self.names.len();
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        self.adj.push(HashSet::new());
+        id
+    }
+
+    pub fn add_edge(&mut self, a: &str, b: &str) {
+        let a = self.intern(a);
+        let b = self.intern(b);
+        if a != b {
+            self.adj[a].insert(b);
+            self.adj[b].insert(a);
+        }
+    }
+
+    pub fn id(&self, name: &str) -> Option<usize> {
+        self.ids.get(name).cloned()
+    }
+
+    pub fn name(&self, id: usize) -> &str {
+        &self.names[id]
+    }
+
+    pub fn names<'a>(&'a self, ids: &[usize]) -> Vec<&'a str> {
+        ids.iter().map(|&id| self.name(id)).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn nodes(&self) -> std::ops::Range<usize> {
+        0..self.len()
+    }
+
+    pub fn neighbors(&self, id: usize) -> &HashSet<usize> {
+        &self.adj[id]
+    }
+
+    pub fn has_edge(&self, a: usize, b: usize) -> bool {
+        self.adj[a].contains(&b)
+    }
+
+    pub fn degree(&self, id: usize) -> usize {
+        self.adj[id].len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.adj.iter().map(|n| n.len()).sum::<usize>() / 2
+    }
+
+    pub fn degree_stats(&self) -> Option<DegreeStats> {
+        if self.is_empty() {
+            return None;
+        }
+        let degrees = self.nodes().map(|id| self.degree(id)).collect::<Vec<_>>();
+        Some(DegreeStats {
+            min: *degrees.iter().min().unwrap(),
+            max: *degrees.iter().max().unwrap(),
+            mean: degrees.iter().sum::<usize>() as f64 / degrees.len() as f64,
+        })
+    }
+
+    pub fn components(&self) -> Vec<Vec<usize>> {
+        let mut dsu = Dsu::new(self.len());
+        for a in self.nodes() {
+            for &b in self.adj[a].iter() {
+                dsu.union(a, b);
+            }
+        }
+        let mut components: HashMap<usize, Vec<usize>> = HashMap::new();
+        for id in self.nodes() {
+            components.entry(dsu.find(id)).or_default().push(id);
+        }
+        let mut components = components.into_values().collect::<Vec<_>>();
+        components.sort_by_key(|c| c[0]);
+        components
+    }
+
+    // Every triangle exactly once, as ids in increasing order
+    pub fn triangles(&self) -> Vec<[usize; 3]> {
+        let mut triangles = vec![];
+        for a in self.nodes() {
+            for &b in self.adj[a].iter().filter(|&&b| b > a) {
+                for &c in self.adj[b].iter().filter(|&&c| c > b) {
+                    if self.has_edge(a, c) {
+                        triangles.push([a, b, c]);
+                    }
+                }
+            }
+        }
+        triangles
+    }
+
+    // Bron-Kerbosch with pivoting; ids are sorted
+    pub fn maximum_clique(&self) -> Vec<usize> {
+        let mut best = vec![];
+        self.bron_kerbosch(
+            &mut vec![],
+            self.nodes().collect(),
+            HashSet::new(),
+            &mut |clique| {
+                if clique.len() > best.len() {
+                    best = clique.to_vec();
+                }
+            },
+        );
+        best.sort();
+        best
+    }
+
+    pub fn maximal_cliques(&self) -> Vec<Vec<usize>> {
+        let mut cliques = vec![];
+        self.bron_kerbosch(
+            &mut vec![],
+            self.nodes().collect(),
+            HashSet::new(),
+            &mut |clique| {
+                let mut clique = clique.to_vec();
+                clique.sort();
+                cliques.push(clique);
+            },
+        );
+        cliques
+    }
+
+    fn bron_kerbosch(
+        &self,
+        r: &mut Vec<usize>,
+        mut p: HashSet<usize>,
+        mut x: HashSet<usize>,
+        report: &mut impl FnMut(&[usize]),
+    ) {
+        if p.is_empty() && x.is_empty() {
+            report(r);
+            return;
+        }
+        let pivot = *p
+            .iter()
+            .chain(x.iter())
+            .max_by_key(|&&u| self.adj[u].iter().filter(|v| p.contains(v)).count())
+            .unwrap();
+        let candidates = p
+            .iter()
+            .filter(|v| !self.adj[pivot].contains(v))
+            .cloned()
+            .collect::<Vec<_>>();
+        for v in candidates {
+            r.push(v);
+            self.bron_kerbosch(
+                r,
+                p.intersection(&self.adj[v]).cloned().collect(),
+                x.intersection(&self.adj[v]).cloned().collect(),
+                report,
+            );
+            r.pop();
+            p.remove(&v);
+            x.insert(v);
+        }
+    }
+}
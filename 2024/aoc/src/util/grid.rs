@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 #[allow(unused)]
 pub fn parse_input_to_grid(input: &str) -> HashMap<(i32, i32), char> {
@@ -14,15 +14,102 @@ pub fn parse_input_to_grid(input: &str) -> HashMap<(i32, i32), char> {
 }
 
 #[allow(unused)]
-pub fn print_grid(grid: &HashMap<(i32, i32), char>) {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Colour {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+}
+
+impl Colour {
+    fn ansi_code(&self) -> u8 {
+        match self {
+            Colour::Red => 31,
+            Colour::Green => 32,
+            Colour::Yellow => 33,
+            Colour::Blue => 34,
+            Colour::Magenta => 35,
+            Colour::Cyan => 36,
+        }
+    }
+}
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderMode {
+    Plain,
+    Ansi,
+}
+
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub struct Overlay {
+    pub points: HashSet<(i32, i32)>,
+    pub c: Option<char>,
+    pub colour: Option<Colour>,
+}
+
+#[allow(unused)]
+impl Overlay {
+    pub fn new(points: impl IntoIterator<Item = (i32, i32)>) -> Self {
+        Self {
+            points: points.into_iter().collect(),
+            c: None,
+            colour: None,
+        }
+    }
+
+    pub fn with_char(mut self, c: char) -> Self {
+        self.c = Some(c);
+        self
+    }
+
+    pub fn with_colour(mut self, colour: Colour) -> Self {
+        self.colour = Some(colour);
+        self
+    }
+}
+
+// Later overlays win over earlier ones. In plain mode an overlay without a char
+// leaves the base char untouched, since colour is the only thing it carries.
+#[allow(unused)]
+pub fn render_grid(
+    grid: &HashMap<(i32, i32), char>,
+    overlays: &[Overlay],
+    mode: RenderMode,
+) -> String {
+    if grid.is_empty() {
+        return String::new();
+    }
     let min_i = *grid.keys().map(|(i, _)| i).min().unwrap();
     let max_i = *grid.keys().map(|(i, _)| i).max().unwrap();
     let min_j = *grid.keys().map(|(_, j)| j).min().unwrap();
     let max_j = *grid.keys().map(|(_, j)| j).max().unwrap();
+    let mut out = String::new();
     for i in min_i..=max_i {
         for j in min_j..=max_j {
-            print!("{}", grid.get(&(i, j)).unwrap_or(&' '));
+            let mut c = *grid.get(&(i, j)).unwrap_or(&' ');
+            let mut colour = None;
+            for overlay in overlays.iter().filter(|o| o.points.contains(&(i, j))) {
+                c = overlay.c.unwrap_or(c);
+                colour = overlay.colour.or(colour);
+            }
+            match (mode, colour) {
+                (RenderMode::Ansi, Some(colour)) => {
+                    out.push_str(&format!("\x1b[{}m{}\x1b[0m", colour.ansi_code(), c));
+                }
+                _ => out.push(c),
+            }
         }
-        println!();
+        out.push('\n');
     }
-}
\ No newline at end of file
+    out
+}
+
+#[allow(unused)]
+pub fn print_grid(grid: &HashMap<(i32, i32), char>) {
+    print!("{}", render_grid(grid, &[], RenderMode::Plain));
+}
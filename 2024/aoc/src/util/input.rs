@@ -0,0 +1,161 @@
+#[cfg(feature = "embed-inputs")]
+use crate::util::embedded;
+use std::{env, fmt, fs, path::Path};
+
+pub const DEFAULT_INPUT_FILE: &str = "input.txt";
+// Set to anything to also hear about lints that are routine
+const VERBOSE_VAR: &str = "AOC_VERBOSE";
+
+// Something off about an input file. The first three are fixed by
+// `normalize`; tabs and non-ASCII characters are only reported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Lint {
+    Bom,
+    Crlf { count: usize },
+    TrailingNewline { count: usize },
+    Tabs { lines: Vec<usize> },
+    NonAscii { lines: Vec<usize> },
+}
+
+impl Lint {
+    pub fn is_fixed(&self) -> bool {
+        matches!(
+            self,
+            Lint::Bom | Lint::Crlf { .. } | Lint::TrailingNewline { .. }
+        )
+    }
+
+    // Every saved input ends in a newline, so stripping it isn't news
+    pub fn is_routine(&self) -> bool {
+        matches!(self, Lint::TrailingNewline { .. })
+    }
+}
+
+// 1-based line numbers, the first few only
+fn line_list(lines: &[usize]) -> String {
+    let mut list = lines
+        .iter()
+        .take(5)
+        .map(|l| l.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    if lines.len() > 5 {
+        list.push_str(&format!(" and {} more", lines.len() - 5));
+    }
+    list
+}
+
+fn plural(count: usize) -> &'static str {
+    if count == 1 {
+        ""
+    } else {
+        "s"
+    }
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Lint::Bom => write!(f, "removed byte order mark"),
+            Lint::Crlf { count } => {
+                write!(f, "converted {} CRLF line ending{}", count, plural(*count))
+            }
+            Lint::TrailingNewline { count } => {
+                write!(f, "stripped {} trailing newline{}", count, plural(*count))
+            }
+            Lint::Tabs { lines } => {
+                write!(
+                    f,
+                    "tabs on line{} {}",
+                    plural(lines.len()),
+                    line_list(lines)
+                )
+            }
+            Lint::NonAscii { lines } => write!(
+                f,
+                "non-ASCII characters on line{} {}",
+                plural(lines.len()),
+                line_list(lines)
+            ),
+        }
+    }
+}
+
+fn lines_with(text: &str, pred: impl Fn(char) -> bool) -> Vec<usize> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| line.chars().any(&pred))
+        .map(|(i, _)| i + 1)
+        .collect()
+}
+
+// Text the parsers can rely on: no BOM, "\n" line endings and no trailing
+// newline. Also lists everything that was wrong with `raw`.
+pub fn normalize(raw: &str) -> (String, Vec<Lint>) {
+    let mut lints = vec![];
+    let mut text = raw;
+    if let Some(rest) = text.strip_prefix('\u{feff}') {
+        lints.push(Lint::Bom);
+        text = rest;
+    }
+    let crlf = text.matches("\r\n").count();
+    let mut text = if crlf > 0 {
+        lints.push(Lint::Crlf { count: crlf });
+        text.replace("\r\n", "\n")
+    } else {
+        text.to_string()
+    };
+    let trimmed = text.trim_end_matches('\n').len();
+    if trimmed < text.len() {
+        lints.push(Lint::TrailingNewline {
+            count: text.len() - trimmed,
+        });
+        text.truncate(trimmed);
+    }
+    let tabs = lines_with(&text, |c| c == '\t');
+    if !tabs.is_empty() {
+        lints.push(Lint::Tabs { lines: tabs });
+    }
+    let non_ascii = lines_with(&text, |c| !c.is_ascii());
+    if !non_ascii.is_empty() {
+        lints.push(Lint::NonAscii { lines: non_ascii });
+    }
+    (text, lints)
+}
+
+#[cfg(not(feature = "embed-inputs"))]
+fn read(this_dir: &Path, file_name: Option<&str>) -> String {
+    fs::read_to_string(this_dir.join(file_name.unwrap_or(DEFAULT_INPUT_FILE))).unwrap()
+}
+
+// The embedded input.txt unless a file is named; a named file comes from disk
+// if it's there, so edited examples still work
+#[cfg(feature = "embed-inputs")]
+fn read(this_dir: &Path, file_name: Option<&str>) -> String {
+    let day = this_dir.file_name().unwrap().to_str().unwrap();
+    let embedded = |name| {
+        embedded::get(day, name)
+            .unwrap_or_else(|| panic!("{}/{} is not embedded", day, name))
+            .to_string()
+    };
+    match file_name {
+        None => embedded(DEFAULT_INPUT_FILE),
+        Some(name) => fs::read_to_string(this_dir.join(name)).unwrap_or_else(|_| embedded(name)),
+    }
+}
+
+// Reads `args[0]` (or input.txt) from the directory of `this_file`, the
+// caller's `file!()`, and normalizes it. Lints go to stderr so they don't
+// get mixed up with the answers; routine ones only if AOC_VERBOSE is set.
+pub fn load(this_file: &str, args: &[String]) -> String {
+    let this_dir = Path::new(this_file).parent().unwrap();
+    let file_name = args.first().map_or(DEFAULT_INPUT_FILE, |s| s.as_str());
+    let raw = read(this_dir, args.first().map(|s| s.as_str()));
+    let (text, lints) = normalize(&raw);
+    let verbose = env::var_os(VERBOSE_VAR).is_some();
+    for lint in lints.iter().filter(|lint| verbose || !lint.is_routine()) {
+        let kind = if lint.is_fixed() { "fixed" } else { "warning" };
+        eprintln!("{}: {}: {}", file_name, kind, lint);
+    }
+    text
+}
@@ -0,0 +1,282 @@
+use std::{
+    fmt,
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+#[allow(unused)]
+pub fn gcd(a: i64, b: i64) -> i64 {
+    ext_gcd(a, b).0
+}
+
+#[allow(unused)]
+pub fn lcm(a: i64, b: i64) -> i64 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    (a / gcd(a, b) * b).abs()
+}
+
+// (g, x, y) such that a * x + b * y == g == gcd(a, b), with g >= 0
+#[allow(unused)]
+pub fn ext_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut r0, mut r1) = (a as i128, b as i128);
+    let (mut x0, mut x1) = (1_i128, 0_i128);
+    let (mut y0, mut y1) = (0_i128, 1_i128);
+    while r1 != 0 {
+        let q = r0.div_euclid(r1);
+        (r0, r1) = (r1, r0 - q * r1);
+        (x0, x1) = (x1, x0 - q * x1);
+        (y0, y1) = (y1, y0 - q * y1);
+    }
+    if r0 < 0 {
+        (r0, x0, y0) = (-r0, -x0, -y0);
+    }
+    (r0 as i64, x0 as i64, y0 as i64)
+}
+
+#[allow(unused)]
+pub fn mod_inv(a: i64, m: i64) -> Option<i64> {
+    let (g, x, _) = ext_gcd(a, m);
+    if g != 1 {
+        return None;
+    }
+    Some(x.rem_euclid(m))
+}
+
+// Solve x = r (mod m) for every (r, m); returns (x, lcm of the moduli) with
+// 0 <= x < lcm. Moduli need not be coprime, as long as the residues agree.
+// None if they don't, or if the lcm doesn't fit in an i64.
+#[allow(unused)]
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    let mut x: i128 = 0;
+    let mut m: i128 = 1;
+    for &(r2, m2) in congruences {
+        assert!(m2 > 0, "Invalid modulus: {}", m2);
+        let (r2, m2) = ((r2 as i128).rem_euclid(m2 as i128), m2 as i128);
+        let (g, p, _) = ext_gcd(i64::try_from(m).ok()?, m2 as i64);
+        let g = g as i128;
+        if (r2 - x) % g != 0 {
+            return None;
+        }
+        let l = m / g * m2;
+        let k = ((r2 - x) / g % (m2 / g) * p as i128).rem_euclid(m2 / g);
+        x = (x + m * k).rem_euclid(l);
+        m = l;
+    }
+    Some((i64::try_from(x).ok()?, i64::try_from(m).ok()?))
+}
+
+// One particular solution (x, y) of a * x + b * y == c, plus the step
+// (dx, dy) such that every solution is (x + k * dx, y + k * dy). The
+// particular solution has 0 <= x < |dx| when b != 0, so it stays small; None
+// if there's no solution, or if even that one doesn't fit in an i64.
+#[allow(unused)]
+pub fn solve_diophantine(a: i64, b: i64, c: i64) -> Option<((i64, i64), (i64, i64))> {
+    if a == 0 && b == 0 {
+        return None;
+    }
+    let (g, x, y) = ext_gcd(a, b);
+    let (g, x, y) = (g as i128, x as i128, y as i128);
+    let (a, b, c) = (a as i128, b as i128, c as i128);
+    if c % g != 0 {
+        return None;
+    }
+    let f = c / g;
+    let (x, y) = if b == 0 {
+        (x * f, y * f)
+    } else {
+        let x = (x * f).rem_euclid((b / g).abs());
+        (x, (c - a * x) / b)
+    };
+    let fit = |v: i128| i64::try_from(v).ok();
+    Some(((fit(x)?, fit(y)?), (fit(b / g)?, fit(-a / g)?)))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    num: i128,
+    den: i128,
+}
+
+#[allow(unused)]
+impl Rational {
+    pub fn new(num: i128, den: i128) -> Self {
+        assert!(den != 0, "Zero denominator");
+        let g = gcd_i128(num, den);
+        let sign = if den < 0 { -1 } else { 1 };
+        Self {
+            num: sign * num / g,
+            den: sign * den / g,
+        }
+    }
+
+    pub fn zero() -> Self {
+        Self::from(0)
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.num == 0
+    }
+
+    pub fn is_integer(self) -> bool {
+        self.den == 1
+    }
+
+    pub fn to_integer(self) -> Option<i64> {
+        if self.is_integer() {
+            i64::try_from(self.num).ok()
+        } else {
+            None
+        }
+    }
+
+    pub fn numer(self) -> i128 {
+        self.num
+    }
+
+    pub fn denom(self) -> i128 {
+        self.den
+    }
+}
+
+fn gcd_i128(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.max(1)
+}
+
+impl From<i64> for Rational {
+    fn from(n: i64) -> Self {
+        Self {
+            num: n as i128,
+            den: 1,
+        }
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.den == 1 {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+impl Add for Rational {
+    type Output = Self;
+    fn add(self, o: Self) -> Self {
+        Self::new(self.num * o.den + o.num * self.den, self.den * o.den)
+    }
+}
+
+impl Sub for Rational {
+    type Output = Self;
+    fn sub(self, o: Self) -> Self {
+        self + -o
+    }
+}
+
+impl Mul for Rational {
+    type Output = Self;
+    fn mul(self, o: Self) -> Self {
+        Self::new(self.num * o.num, self.den * o.den)
+    }
+}
+
+impl Div for Rational {
+    type Output = Self;
+    fn div(self, o: Self) -> Self {
+        Self::new(self.num * o.den, self.den * o.num)
+    }
+}
+
+impl Neg for Rational {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self {
+            num: -self.num,
+            den: self.den,
+        }
+    }
+}
+
+#[allow(unused)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinearSolution {
+    None,
+    Unique(Vec<Rational>),
+    // every solution is `particular` plus any combination of `nullspace`
+    Infinite {
+        particular: Vec<Rational>,
+        nullspace: Vec<Vec<Rational>>,
+    },
+}
+
+// Solve a * x == b exactly by Gauss-Jordan elimination over the rationals
+#[allow(unused)]
+pub fn solve_linear(a: &[Vec<i64>], b: &[i64]) -> LinearSolution {
+    assert_eq!(a.len(), b.len(), "Row count mismatch");
+    let n_cols = a.first().map(|row| row.len()).unwrap_or(0);
+    let mut m: Vec<Vec<Rational>> = a
+        .iter()
+        .zip(b)
+        .map(|(row, &rhs)| {
+            assert_eq!(row.len(), n_cols, "Ragged matrix");
+            row.iter()
+                .chain([rhs].iter())
+                .map(|&v| Rational::from(v))
+                .collect()
+        })
+        .collect();
+
+    let mut pivots: Vec<usize> = vec![];
+    let mut r = 0;
+    for c in 0..n_cols {
+        let Some(p) = (r..m.len()).find(|&i| !m[i][c].is_zero()) else {
+            continue;
+        };
+        m.swap(r, p);
+        let pivot = m[r][c];
+        m[r] = m[r].iter().map(|&v| v / pivot).collect();
+        for i in 0..m.len() {
+            if i != r && !m[i][c].is_zero() {
+                let f = m[i][c];
+                m[i] = m[i].iter().zip(&m[r]).map(|(&v, &w)| v - f * w).collect();
+            }
+        }
+        pivots.push(c);
+        r += 1;
+    }
+
+    if m[r..].iter().any(|row| !row[n_cols].is_zero()) {
+        return LinearSolution::None;
+    }
+
+    let mut particular = vec![Rational::zero(); n_cols];
+    for (i, &c) in pivots.iter().enumerate() {
+        particular[c] = m[i][n_cols];
+    }
+    if pivots.len() == n_cols {
+        return LinearSolution::Unique(particular);
+    }
+
+    let nullspace = (0..n_cols)
+        .filter(|c| !pivots.contains(c))
+        .map(|free| {
+            let mut v = vec![Rational::zero(); n_cols];
+            v[free] = Rational::from(1);
+            for (i, &c) in pivots.iter().enumerate() {
+                v[c] = -m[i][free];
+            }
+            v
+        })
+        .collect();
+    LinearSolution::Infinite {
+        particular,
+        nullspace,
+    }
+}
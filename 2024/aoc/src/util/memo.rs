@@ -0,0 +1,64 @@
+use cached::{Cached, UnboundCache};
+use std::{fmt, hash::Hash};
+
+// Cache for a recursive function. Each call site owns its own `Memo`, so the
+// cache is dropped (or `clear`ed) along with the input it was built for.
+pub struct Memo<K, V> {
+    cache: UnboundCache<K, V>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub size: usize,
+}
+
+impl fmt::Display for MemoStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} hits, {} misses, {} entries",
+            self.hits, self.misses, self.size
+        )
+    }
+}
+
+#[allow(unused)]
+impl<K: Hash + Eq + Clone, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Self {
+            cache: UnboundCache::new(),
+        }
+    }
+
+    // Cached value for `key`, or `f(self, &key)` on a miss. `f` gets the memo
+    // back so it can recurse through it.
+    pub fn get(&mut self, key: K, f: impl FnOnce(&mut Self, &K) -> V) -> V {
+        if let Some(v) = self.cache.cache_get(&key) {
+            return v.clone();
+        }
+        let v = f(self, &key);
+        self.cache.cache_set(key, v.clone());
+        v
+    }
+
+    pub fn clear(&mut self) {
+        self.cache.cache_clear();
+        self.cache.cache_reset_metrics();
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        MemoStats {
+            hits: self.cache.cache_hits().unwrap_or(0),
+            misses: self.cache.cache_misses().unwrap_or(0),
+            size: self.cache.cache_size(),
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
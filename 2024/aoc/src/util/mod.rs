@@ -1 +1,13 @@
-pub mod grid;
\ No newline at end of file
+pub mod cycle;
+pub mod dsu;
+#[cfg(feature = "embed-inputs")]
+pub mod embedded;
+pub mod graph;
+pub mod grid;
+pub mod input;
+pub mod math;
+pub mod memo;
+pub mod parse;
+pub mod prop;
+pub mod simulation;
+pub mod torus;
@@ -0,0 +1,170 @@
+use regex::Regex;
+use std::{fmt, str::FromStr};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// A run of non-blank lines; `start` is the 1-based line number of the first one
+#[derive(Debug, Clone)]
+pub struct Block<'a> {
+    pub start: usize,
+    pub lines: Vec<&'a str>,
+}
+
+#[allow(unused)]
+impl Block<'_> {
+    pub fn line_number(&self, i: usize) -> usize {
+        self.start + i
+    }
+
+    pub fn error(&self, i: usize, message: impl Into<String>) -> ParseError {
+        ParseError::new(self.line_number(i), message)
+    }
+
+    pub fn expect_len(&self, n: usize) -> Result<(), ParseError> {
+        if self.lines.len() != n {
+            return Err(self.error(
+                0,
+                format!("expected {} lines, found {}", n, self.lines.len()),
+            ));
+        }
+        Ok(())
+    }
+}
+
+// 1-based line numbers, with any trailing '\r' removed
+#[allow(unused)]
+pub fn lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim_end_matches('\r')))
+}
+
+#[allow(unused)]
+pub fn blocks(input: &str) -> Vec<Block<'_>> {
+    let mut blocks = vec![];
+    let mut current: Option<Block> = None;
+    for (n, line) in lines(input) {
+        if line.trim().is_empty() {
+            blocks.extend(current.take());
+            continue;
+        }
+        current
+            .get_or_insert_with(|| Block {
+                start: n,
+                lines: vec![],
+            })
+            .lines
+            .push(line);
+    }
+    blocks.extend(current);
+    blocks
+}
+
+// Exactly `n` blocks; otherwise an error at the first extra block, or just
+// past the end of the input if there are too few
+#[allow(unused)]
+pub fn expect_blocks(input: &str, n: usize) -> Result<Vec<Block<'_>>, ParseError> {
+    let blocks = blocks(input);
+    if blocks.len() == n {
+        return Ok(blocks);
+    }
+    let line = match blocks.get(n) {
+        Some(extra) => extra.start,
+        None => input.lines().count() + 1,
+    };
+    Err(ParseError::new(
+        line,
+        format!(
+            "expected {} blocks separated by blank lines, found {}",
+            n,
+            blocks.len()
+        ),
+    ))
+}
+
+// Every integer in the line; a '-' directly before digits is a sign unless it
+// follows another digit (so "3-4" is 3 and 4, not 3 and -4)
+#[allow(unused)]
+pub fn ints(line_no: usize, line: &str) -> Result<Vec<i64>, ParseError> {
+    let bytes = line.as_bytes();
+    let mut values = vec![];
+    let mut i = 0;
+    while i < bytes.len() {
+        let negative = bytes[i] == b'-'
+            && bytes.get(i + 1).is_some_and(|b| b.is_ascii_digit())
+            && (i == 0 || !bytes[i - 1].is_ascii_digit());
+        if !negative && !bytes[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        i += 1;
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        values.push(value(line_no, &line[start..i])?);
+    }
+    Ok(values)
+}
+
+// "key: value" -> ("key", "value"), both trimmed
+#[allow(unused)]
+pub fn key_value<'a>(
+    line_no: usize,
+    line: &'a str,
+    sep: &str,
+) -> Result<(&'a str, &'a str), ParseError> {
+    line.split_once(sep)
+        .map(|(k, v)| (k.trim(), v.trim()))
+        .ok_or_else(|| ParseError::new(line_no, format!("missing '{}' in {:?}", sep, line)))
+}
+
+// "a|b" -> (a, b)
+#[allow(unused)]
+pub fn pair<T: FromStr>(line_no: usize, line: &str, sep: char) -> Result<(T, T), ParseError> {
+    let (a, b) = line
+        .split_once(sep)
+        .ok_or_else(|| ParseError::new(line_no, format!("missing '{}' in {:?}", sep, line)))?;
+    Ok((value(line_no, a)?, value(line_no, b)?))
+}
+
+#[allow(unused)]
+pub fn value<T: FromStr>(line_no: usize, s: &str) -> Result<T, ParseError> {
+    s.trim()
+        .parse::<T>()
+        .map_err(|_| ParseError::new(line_no, format!("invalid value {:?}", s.trim())))
+}
+
+// All capture groups of `re` (excluding the whole match)
+#[allow(unused)]
+pub fn captures<'a>(line_no: usize, re: &Regex, line: &'a str) -> Result<Vec<&'a str>, ParseError> {
+    let c = re
+        .captures(line)
+        .ok_or_else(|| ParseError::new(line_no, format!("{:?} does not match {}", line, re)))?;
+    Ok(c.iter()
+        .skip(1)
+        .map(|m| m.map(|m| m.as_str()).unwrap_or(""))
+        .collect())
+}
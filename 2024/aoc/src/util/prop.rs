@@ -0,0 +1,125 @@
+use rand::{rngs::StdRng, SeedableRng};
+use std::{fmt, fs, path::PathBuf};
+
+// A randomised check that two implementations agree. `check` returns None
+// when the case is outside what both sides support, so it counts as neither a
+// pass nor a failure.
+pub struct Property<T> {
+    pub name: &'static str,
+    // `file!()` of the day; failing cases are saved next to its inputs
+    pub file: &'static str,
+    pub generate: fn(&mut StdRng) -> T,
+    // Candidates strictly "smaller" than the input, simplest first
+    pub shrink: fn(&T) -> Vec<T>,
+    pub check: fn(&T) -> Option<Result<(), String>>,
+    // Written to disk for the shrunk failure; ideally a valid puzzle input
+    pub show: fn(&T) -> String,
+}
+
+#[derive(Debug)]
+pub struct Failure {
+    pub name: &'static str,
+    pub seed: u64,
+    pub case: usize,
+    pub shrinks: usize,
+    pub message: String,
+    pub input: String,
+    pub path: PathBuf,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{}: case {} (seed {}) failed after {} shrinks: {}",
+            self.name, self.case, self.seed, self.shrinks, self.message
+        )?;
+        writeln!(f, "{}", self.input)?;
+        write!(f, "saved to {}", self.path.display())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Outcome {
+    pub passed: usize,
+    pub skipped: usize,
+}
+
+// Type-erased so properties over different inputs can share a list
+pub trait Check {
+    fn name(&self) -> &'static str;
+    fn run(&self, seed: u64, cases: usize) -> Result<Outcome, Failure>;
+}
+
+// Writes a failing case next to the inputs of the day `file` belongs to
+pub fn save_failure(file: &str, name: &str, input: &str) -> PathBuf {
+    let dir = std::path::Path::new(file).parent().unwrap();
+    let path = dir.join(format!("failure-{}.txt", name));
+    fs::write(&path, input).unwrap();
+    path
+}
+
+impl<T> Property<T> {
+    // Greedily take the first smaller candidate that still fails, until none do
+    fn minimise(&self, mut input: T, mut message: String) -> (T, String, usize) {
+        let mut shrinks = 0;
+        'outer: loop {
+            for candidate in (self.shrink)(&input) {
+                if let Some(Err(m)) = (self.check)(&candidate) {
+                    input = candidate;
+                    message = m;
+                    shrinks += 1;
+                    continue 'outer;
+                }
+            }
+            return (input, message, shrinks);
+        }
+    }
+}
+
+impl<T> Check for Property<T> {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn run(&self, seed: u64, cases: usize) -> Result<Outcome, Failure> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut outcome = Outcome {
+            passed: 0,
+            skipped: 0,
+        };
+        for case in 0..cases {
+            let input = (self.generate)(&mut rng);
+            match (self.check)(&input) {
+                None => outcome.skipped += 1,
+                Some(Ok(())) => outcome.passed += 1,
+                Some(Err(message)) => {
+                    let (input, message, shrinks) = self.minimise(input, message);
+                    let input = (self.show)(&input);
+                    return Err(Failure {
+                        name: self.name,
+                        seed,
+                        case,
+                        shrinks,
+                        message,
+                        path: save_failure(self.file, self.name, &input),
+                        input,
+                    });
+                }
+            }
+        }
+        Ok(outcome)
+    }
+}
+
+// Shrink candidates for a single number: towards `min`, big jumps first
+#[allow(unused)]
+pub fn shrink_int(x: i64, min: i64) -> Vec<i64> {
+    let mut candidates = vec![];
+    let mut d = x - min;
+    while d > 0 {
+        candidates.push(x - d);
+        d /= 2;
+    }
+    candidates
+}
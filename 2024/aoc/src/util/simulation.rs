@@ -0,0 +1,63 @@
+use crate::util::cycle::{self, Cycle};
+use std::hash::Hash;
+
+#[allow(unused)]
+pub trait Simulation {
+    type Snapshot: Clone;
+
+    // Advance one step; false once there is nothing left to do
+    fn step(&mut self) -> bool;
+
+    fn snapshot(&self) -> Self::Snapshot;
+
+    fn restore(&mut self, snapshot: Self::Snapshot);
+
+    // Number of steps actually taken
+    fn run(&mut self, n: usize) -> usize {
+        self.run_observed(n, &mut |_, _| {})
+    }
+
+    // `observer` sees the state after every step, along with the step count
+    fn run_observed(&mut self, n: usize, observer: &mut dyn FnMut(usize, &Self)) -> usize {
+        for i in 0..n {
+            if !self.step() {
+                return i;
+            }
+            observer(i + 1, self);
+        }
+        n
+    }
+
+    // Steps taken until `pred` holds, or None if the simulation finished first
+    fn run_until(&mut self, mut pred: impl FnMut(&Self) -> bool) -> Option<usize> {
+        let mut i = 0;
+        while !pred(self) {
+            if !self.step() {
+                return None;
+            }
+            i += 1;
+        }
+        Some(i)
+    }
+
+    fn run_to_end(&mut self) -> usize {
+        self.run(usize::MAX)
+    }
+
+    // Detect a repeating state by hashing snapshots; leaves `self` untouched
+    fn find_cycle(&self) -> Option<Cycle>
+    where
+        Self: Clone,
+        Self::Snapshot: Eq + Hash,
+    {
+        let mut sim = self.clone();
+        cycle::find_cycle(self.snapshot(), |snapshot| {
+            sim.restore(snapshot.clone());
+            if sim.step() {
+                Some(sim.snapshot())
+            } else {
+                None
+            }
+        })
+    }
+}
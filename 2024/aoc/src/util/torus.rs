@@ -0,0 +1,47 @@
+#[allow(unused)]
+pub fn wrap(x: i64, size: i64) -> i64 {
+    x.rem_euclid(size)
+}
+
+// (x + v * n) mod size, without overflowing for large step counts
+#[allow(unused)]
+pub fn wrap_after(x: i64, v: i64, n: i64, size: i64) -> i64 {
+    (x as i128 + v as i128 * n as i128).rem_euclid(size as i128) as i64
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Torus {
+    pub width: i64,
+    pub height: i64,
+}
+
+#[allow(unused)]
+impl Torus {
+    pub fn new(width: i64, height: i64) -> Self {
+        assert!(width > 0 && height > 0, "Invalid torus size");
+        Self { width, height }
+    }
+
+    pub fn wrap(&self, p: (i64, i64)) -> (i64, i64) {
+        (wrap(p.0, self.width), wrap(p.1, self.height))
+    }
+
+    pub fn step(&self, p: (i64, i64), v: (i64, i64)) -> (i64, i64) {
+        self.position_after(p, v, 1)
+    }
+
+    pub fn position_after(&self, p: (i64, i64), v: (i64, i64), n: i64) -> (i64, i64) {
+        (
+            wrap_after(p.0, v.0, n, self.width),
+            wrap_after(p.1, v.1, n, self.height),
+        )
+    }
+
+    pub fn contains(&self, p: (i64, i64)) -> bool {
+        (0..self.width).contains(&p.0) && (0..self.height).contains(&p.1)
+    }
+
+    pub fn area(&self) -> i64 {
+        self.width * self.height
+    }
+}
@@ -0,0 +1,160 @@
+use crate::util::input::DEFAULT_INPUT_FILE;
+use std::{
+    collections::HashMap,
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// "Part 1: ..." lines, keyed by part
+type Answers = HashMap<String, String>;
+
+fn parse_answers(output: &str) -> Answers {
+    output
+        .lines()
+        .filter(|line| line.starts_with("Part "))
+        .filter_map(|line| line.split_once(": "))
+        .map(|(part, answer)| (part.to_string(), answer.trim().to_string()))
+        .collect()
+}
+
+// Golden answers for `input*` live in `answers*` next to it, e.g.
+// input.txt.example.1 -> answers.txt.example.1
+fn golden_answers(day_dir: &Path, file_name: &str) -> Option<Answers> {
+    let answers_name = file_name.replacen("input", "answers", 1);
+    if answers_name == file_name {
+        return None;
+    }
+    let answers = fs::read_to_string(day_dir.join(answers_name)).ok()?;
+    Some(parse_answers(&answers))
+}
+
+// Source and input files of the day, with their modification times
+fn snapshot(day_dir: &Path) -> HashMap<PathBuf, SystemTime> {
+    let Ok(entries) = fs::read_dir(day_dir) else {
+        return HashMap::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            let name = path.file_name().unwrap().to_string_lossy();
+            name.ends_with(".rs") || name.starts_with("input") || name.starts_with("answers")
+        })
+        .filter_map(|path| {
+            let modified = fs::metadata(&path).ok()?.modified().ok()?;
+            Some((path, modified))
+        })
+        .collect()
+}
+
+fn changed_files(
+    before: &HashMap<PathBuf, SystemTime>,
+    after: &HashMap<PathBuf, SystemTime>,
+) -> Vec<PathBuf> {
+    let mut changed = after
+        .iter()
+        .filter(|(path, modified)| before.get(*path) != Some(modified))
+        .map(|(path, _)| path.clone())
+        .chain(before.keys().filter(|p| !after.contains_key(*p)).cloned())
+        .collect::<Vec<_>>();
+    changed.sort();
+    changed
+}
+
+fn build() -> bool {
+    let mut cargo = Command::new("cargo");
+    cargo.arg("build");
+    if !cfg!(debug_assertions) {
+        cargo.arg("--release");
+    }
+    matches!(cargo.status(), Ok(status) if status.success())
+}
+
+// Runs the day through the (possibly just rebuilt) binary, so new code is used
+fn run_day(exe: &Path, day: usize, file_name: &str) -> Option<String> {
+    let output = Command::new(exe)
+        .arg(day.to_string())
+        .arg(file_name)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        print!("{}", String::from_utf8_lossy(&output.stderr));
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn report(answers: &Answers, previous: Option<&Answers>, golden: Option<&Answers>) {
+    let mut parts = answers.keys().collect::<Vec<_>>();
+    parts.sort();
+    for part in parts {
+        let answer = &answers[part];
+        let mut notes = vec![];
+        match previous.and_then(|p| p.get(part)) {
+            Some(before) if before != answer => notes.push(format!("was {}", before)),
+            Some(_) => notes.push("unchanged".to_string()),
+            None => {}
+        }
+        match golden.and_then(|g| g.get(part)) {
+            Some(expected) if expected == answer => notes.push("correct".to_string()),
+            Some(expected) => notes.push(format!("WRONG, expected {}", expected)),
+            None => {}
+        }
+        if notes.is_empty() {
+            println!("{}: {}", part, answer);
+        } else {
+            println!("{}: {} ({})", part, answer, notes.join(", "));
+        }
+    }
+}
+
+// aoc watch <day> [input file]
+pub fn main(args: Vec<String>) {
+    let day = args[0].parse::<usize>().unwrap();
+    assert!((1..=25).contains(&day), "Invalid day: {}", day);
+    let file_name = args.get(1).map_or(DEFAULT_INPUT_FILE, |s| s.as_str());
+    let day_dir = Path::new("src").join(format!("day{:02}", day));
+    // resolved up front: once cargo replaces the binary, /proc/self/exe
+    // points at the deleted file
+    let exe = env::current_exe().unwrap();
+
+    let mut files = HashMap::new();
+    let mut previous: Option<Answers> = None;
+    loop {
+        let now = snapshot(&day_dir);
+        let changed = changed_files(&files, &now);
+        if changed.is_empty() {
+            thread::sleep(POLL_INTERVAL);
+            continue;
+        }
+        let first_run = files.is_empty();
+        let rebuild = !first_run
+            && changed
+                .iter()
+                .any(|p| p.extension().is_some_and(|e| e == "rs"));
+        files = now;
+
+        if !first_run {
+            for path in changed.iter() {
+                println!("changed: {}", path.display());
+            }
+        }
+        if rebuild && !build() {
+            println!("build failed, waiting for changes");
+            continue;
+        }
+        let Some(output) = run_day(&exe, day, file_name) else {
+            println!("day {} failed, waiting for changes", day);
+            continue;
+        };
+        let answers = parse_answers(&output);
+        let golden = golden_answers(&day_dir, file_name);
+        report(&answers, previous.as_ref(), golden.as_ref());
+        previous = Some(answers);
+    }
+}